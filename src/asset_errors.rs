@@ -0,0 +1,283 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+#[cfg(debug_assertions)]
+use bevy::{asset::AssetLoadFailedEvent, prelude::*};
+
+#[cfg(debug_assertions)]
+use crate::toast::ToastEvent;
+
+/// A data asset (item, character, ...) that failed to load or didn't pass validation
+#[derive(Debug)]
+pub struct DataAssetError {
+    pub path: PathBuf,
+    /// 1-based line and column of the problem, when it can be pinned down
+    pub location: Option<(usize, usize)>,
+    pub kind: DataAssetErrorKind,
+}
+
+#[derive(Debug)]
+pub enum DataAssetErrorKind {
+    Io(std::io::Error),
+    UnsupportedFormat,
+    Syntax(String),
+    UnknownFields(Vec<String>),
+    Invalid(String),
+    /// Another asset this one depends on failed to load
+    Dependency {
+        path: String,
+        message: String,
+    },
+}
+
+/// A semantic problem with otherwise well-formed data, such as "attack_duration must be > 0"
+#[derive(Debug)]
+pub struct ValidationError {
+    /// Name of the offending field, used to find it in the source
+    pub field: &'static str,
+    /// Array and index of the element holding `field`, for fields that show up once per element
+    pub element: Option<(&'static str, usize)>,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            element: None,
+            message: message.into(),
+        }
+    }
+
+    /// Narrows the field down to the `index`-th element of the `array` field
+    pub fn in_element(mut self, array: &'static str, index: usize) -> Self {
+        self.element = Some((array, index));
+        self
+    }
+}
+
+/// Checks that loaded data makes sense beyond what deserializing already guarantees
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+impl DataAssetError {
+    pub fn new(path: &Path, kind: DataAssetErrorKind) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            location: None,
+            kind,
+        }
+    }
+
+    pub fn at(mut self, line: usize, column: usize) -> Self {
+        self.location = Some((line, column));
+        self
+    }
+
+    /// Same as `at` but from a byte offset into `source`
+    pub fn at_offset(self, source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+        self.at(line, column)
+    }
+
+    pub fn io(path: &Path, error: std::io::Error) -> Self {
+        Self::new(path, DataAssetErrorKind::Io(error))
+    }
+
+    pub fn json(path: &Path, error: serde_json::Error) -> Self {
+        let (line, column) = (error.line(), error.column());
+        Self::new(path, DataAssetErrorKind::Syntax(error.to_string())).at(line, column)
+    }
+
+    /// Points at the first place `error.field` shows up in `source`, within its array element if it has one
+    pub fn invalid(path: &Path, source: &str, error: ValidationError) -> Self {
+        let (offset, message) = match error.element {
+            Some((array, index)) => {
+                let offset = find_field(source, array)
+                    .and_then(|offset| find_element(source, offset, index))
+                    .map(|(start, end)| {
+                        // fields left at their default aren't in the source, so fall back to the element itself
+                        find_field(&source[start..end], error.field).map_or(start, |o| start + o)
+                    });
+                (offset, format!("{array}[{index}]: {}", error.message))
+            }
+            None => (find_field(source, error.field), error.message),
+        };
+        let invalid = Self::new(path, DataAssetErrorKind::Invalid(message));
+        match offset {
+            Some(offset) => invalid.at_offset(source, offset),
+            None => invalid,
+        }
+    }
+}
+
+impl Display for DataAssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some((line, column)) = self.location {
+            write!(f, ":{line}:{column}")?;
+        }
+        match &self.kind {
+            DataAssetErrorKind::Io(e) => write!(f, ": failed to read: {e}"),
+            DataAssetErrorKind::UnsupportedFormat => write!(f, ": unsupported format"),
+            DataAssetErrorKind::Syntax(e) => write!(f, ": {e}"),
+            DataAssetErrorKind::UnknownFields(fields) => {
+                write!(f, ": unknown fields: {}", fields.join(", "))
+            }
+            DataAssetErrorKind::Invalid(message) => write!(f, ": {message}"),
+            DataAssetErrorKind::Dependency { path, message } => {
+                write!(f, ": failed to load dependency '{path}': {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DataAssetError {}
+
+/// Byte offset of the first key named `field`, quoted or bare, that is followed by `:` (JSON and RON) or `=` (TOML).
+/// Anything inside a string is skipped
+fn find_field(source: &str, field: &str) -> Option<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    source.match_indices(field).map(|(i, _)| i).find(|&i| {
+        let quoted = source[..i].ends_with('"');
+        let key_start = if quoted { i - 1 } else { i };
+        if in_string(source, key_start) {
+            return false;
+        }
+        let mut rest = &source[i + field.len()..];
+        if quoted {
+            let Some(unquoted) = rest.strip_prefix('"') else {
+                return false;
+            };
+            rest = unquoted;
+        } else if source[..i].chars().next_back().is_some_and(is_word)
+            || rest.chars().next().is_some_and(is_word)
+        {
+            return false;
+        }
+        rest.trim_start().starts_with([':', '='])
+    })
+}
+
+/// Whether `offset` is inside a double quoted string, going by the unescaped quotes before it
+fn in_string(source: &str, offset: usize) -> bool {
+    let mut inside = false;
+    let mut escaped = false;
+    for c in source[..offset].chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if inside => escaped = true,
+            '"' => inside = !inside,
+            _ => (),
+        }
+    }
+    inside
+}
+
+/// Byte range of the `index`-th element of the first array after `offset`
+fn find_element(source: &str, offset: usize, index: usize) -> Option<(usize, usize)> {
+    let open = offset + source[offset..].find('[')?;
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut count = 0;
+    let mut start = None;
+    for (i, c) in source[open..].char_indices() {
+        let i = open + i;
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+        if depth == 1 && start.is_none() && !c.is_whitespace() && c != ',' && c != ']' {
+            start = Some(i);
+        }
+        match c {
+            '"' => in_string = true,
+            '[' | '{' | '(' => depth += 1,
+            ']' | '}' | ')' => depth -= 1,
+            ',' if depth == 1 => {
+                if let Some(start) = start.take() {
+                    if count == index {
+                        return Some((start, i));
+                    }
+                    count += 1;
+                }
+            }
+            _ => (),
+        }
+        if depth == 0 {
+            // the end of the array
+            return start.filter(|_| count == index).map(|start| (start, i));
+        }
+    }
+    None
+}
+
+/// Dev builds show asset failures as toasts, so broken data is noticed without digging through the log
+#[cfg(debug_assertions)]
+pub fn toast_load_failures<A: Asset>(
+    mut events: EventReader<AssetLoadFailedEvent<A>>,
+    mut cmd: Commands,
+) {
+    for event in events.read() {
+        cmd.trigger(ToastEvent(event.error.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_nested_keys() {
+        let source = r#"{"outer": {"inner": 1}}"#;
+        assert_eq!(find_field(source, "inner"), source.find("inner"));
+    }
+
+    #[test]
+    fn skips_values_with_the_same_name() {
+        let source = r#"{"name": "damage", "damage": 3}"#;
+        assert_eq!(
+            find_field(source, "damage"),
+            source.find(r#""damage":"#).map(|i| i + 1)
+        );
+        let source = "name = \"damage\"\ndamage = 3";
+        assert_eq!(
+            find_field(source, "damage"),
+            source.find("\ndamage").map(|i| i + 1)
+        );
+    }
+
+    #[test]
+    fn skips_keys_inside_strings_with_escaped_quotes() {
+        let source = r#"{"note": "not \"id\": here", "id": 2}"#;
+        let quoted = source.find(r#"\"id\""#).unwrap() + 2;
+        assert!(in_string(source, quoted));
+        assert_eq!(
+            find_field(source, "id"),
+            source.rfind(r#""id""#).map(|i| i + 1)
+        );
+    }
+
+    #[test]
+    fn finds_the_nth_element_of_an_array_of_objects() {
+        let source =
+            r#"{"entries": [{"weight": 1}, {"weight": 2, "drop": "a,b]"}, {"weight": 0}]}"#;
+        let entries = find_field(source, "entries").unwrap();
+        let element = |index| find_element(source, entries, index).map(|(s, e)| &source[s..e]);
+        assert_eq!(element(0), Some(r#"{"weight": 1}"#));
+        assert_eq!(element(1), Some(r#"{"weight": 2, "drop": "a,b]"}"#));
+        assert_eq!(element(2), Some(r#"{"weight": 0}"#));
+        assert_eq!(element(3), None);
+    }
+}
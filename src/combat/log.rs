@@ -0,0 +1,254 @@
+use std::{collections::VecDeque, fmt::Display, fs::File};
+
+use bevy::{input::common_conditions::input_toggle_active, prelude::*};
+use bevy_inspector_egui::bevy_egui::{egui, EguiContexts};
+use serde::Serialize;
+
+use crate::{
+    health::{damage::DamageComponent, death::Died, HealthAffectApplied},
+    rpg_data::{conditions::ConditionChanged, dice::DegreeOfSuccess},
+    toast::ToastEvent,
+};
+
+use super::AttackRolled;
+
+const COMBAT_LOG_FILE: &str = "combat_log.json";
+const COMBAT_LOG_CAPACITY: usize = 500;
+
+pub struct CombatLogPlugin;
+
+impl Plugin for CombatLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CombatLog>();
+        app.add_systems(
+            Update,
+            combat_log_panel.run_if(input_toggle_active(false, KeyCode::F3)),
+        );
+        app.add_observer(log_health_affects);
+        app.add_observer(log_deaths);
+        app.add_observer(log_attack_rolls);
+        app.add_observer(log_condition_changes);
+        app.add_observer(export_combat_log);
+    }
+}
+
+/// The most recent combat events of the session, oldest first. Only the last `capacity` entries are kept
+#[derive(Debug, Resource, Serialize)]
+pub struct CombatLog {
+    pub entries: VecDeque<CombatLogEntry>,
+    pub capacity: usize,
+}
+
+impl Default for CombatLog {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::with_capacity(COMBAT_LOG_CAPACITY),
+            capacity: COMBAT_LOG_CAPACITY,
+        }
+    }
+}
+
+impl CombatLog {
+    pub fn push(&mut self, entry: CombatLogEntry) {
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CombatLogEntry {
+    /// Seconds of game time since startup
+    pub time: f32,
+    pub source: Option<String>,
+    pub target: String,
+    pub kind: CombatLogKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum CombatLogKind {
+    Health {
+        damage: Vec<DamageComponent>,
+        absorbed: u32,
+        healing: u32,
+        critical: bool,
+    },
+    Death {
+        final_damage: u32,
+    },
+    AttackRoll {
+        natural: u32,
+        total: i32,
+        dc: i32,
+        degree: DegreeOfSuccess,
+    },
+    Condition {
+        condition: String,
+        value: Option<u32>,
+        active: bool,
+    },
+}
+
+impl Display for CombatLogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{:.1}] ", self.time)?;
+        if let Some(source) = &self.source {
+            write!(f, "{source} -> ")?;
+        }
+        write!(f, "{}: ", self.target)?;
+        match &self.kind {
+            CombatLogKind::Health {
+                damage,
+                absorbed,
+                healing,
+                critical,
+            } => {
+                for component in damage {
+                    write!(f, "{} {:?} ", component.amount, component.damage_type)?;
+                }
+                if *absorbed > 0 {
+                    write!(f, "({absorbed} absorbed) ")?;
+                }
+                if *healing > 0 {
+                    write!(f, "+{healing} HP ")?;
+                }
+                if *critical {
+                    write!(f, "CRIT")?;
+                }
+                Ok(())
+            }
+            CombatLogKind::Death { final_damage } => {
+                write!(f, "died (final damage {final_damage})")
+            }
+            CombatLogKind::AttackRoll {
+                natural,
+                total,
+                dc,
+                degree,
+            } => write!(f, "attack roll {total} ({natural}) vs {dc}: {degree:?}"),
+            CombatLogKind::Condition {
+                condition,
+                value,
+                active,
+            } => match (active, value) {
+                (false, _) => write!(f, "lost {condition}"),
+                (true, Some(value)) => write!(f, "{condition} {value}"),
+                (true, None) => write!(f, "gained {condition}"),
+            },
+        }
+    }
+}
+
+/// Trigger to write the whole combat log to `combat_log.json`
+#[derive(Debug, Event)]
+pub struct ExportCombatLog;
+
+fn label(entity: Entity, names: &Query<&Name>) -> String {
+    match names.get(entity) {
+        Ok(name) => format!("{name} ({entity})"),
+        Err(_) => entity.to_string(),
+    }
+}
+
+fn log_health_affects(
+    trigger: Trigger<HealthAffectApplied>,
+    mut log: ResMut<CombatLog>,
+    names: Query<&Name>,
+    time: Res<Time<Virtual>>,
+) {
+    log.push(CombatLogEntry {
+        time: time.elapsed_secs(),
+        source: trigger.source.map(|e| label(e, &names)),
+        target: label(trigger.entity(), &names),
+        kind: CombatLogKind::Health {
+            damage: trigger.damage.clone(),
+            absorbed: trigger.absorbed,
+            healing: trigger.healing,
+            critical: trigger.critical,
+        },
+    });
+}
+
+fn log_deaths(
+    trigger: Trigger<Died>,
+    mut log: ResMut<CombatLog>,
+    names: Query<&Name>,
+    time: Res<Time<Virtual>>,
+) {
+    log.push(CombatLogEntry {
+        time: time.elapsed_secs(),
+        source: trigger.killer.map(|e| label(e, &names)),
+        target: label(trigger.entity(), &names),
+        kind: CombatLogKind::Death {
+            final_damage: trigger.final_damage,
+        },
+    });
+}
+
+fn log_attack_rolls(
+    trigger: Trigger<AttackRolled>,
+    mut log: ResMut<CombatLog>,
+    names: Query<&Name>,
+    time: Res<Time<Virtual>>,
+) {
+    log.push(CombatLogEntry {
+        time: time.elapsed_secs(),
+        source: Some(label(trigger.attacker, &names)),
+        target: label(trigger.target, &names),
+        kind: CombatLogKind::AttackRoll {
+            natural: trigger.natural,
+            total: trigger.total,
+            dc: trigger.dc,
+            degree: trigger.degree,
+        },
+    });
+}
+
+fn log_condition_changes(
+    trigger: Trigger<ConditionChanged>,
+    mut log: ResMut<CombatLog>,
+    names: Query<&Name>,
+    time: Res<Time<Virtual>>,
+) {
+    log.push(CombatLogEntry {
+        time: time.elapsed_secs(),
+        source: None,
+        target: label(trigger.entity(), &names),
+        kind: CombatLogKind::Condition {
+            condition: trigger.condition.clone(),
+            value: trigger.value,
+            active: trigger.active,
+        },
+    });
+}
+
+fn export_combat_log(_: Trigger<ExportCombatLog>, log: Res<CombatLog>, mut cmd: Commands) {
+    let Ok(file) = File::create(COMBAT_LOG_FILE) else {
+        warn!("Failed to create {COMBAT_LOG_FILE}");
+        return;
+    };
+    if let Err(e) = serde_json::to_writer_pretty(file, log.into_inner()) {
+        warn!("Failed to serialize combat log: {e}");
+        return;
+    }
+    cmd.trigger(ToastEvent(format!("Combat log saved to {COMBAT_LOG_FILE}")));
+}
+
+fn combat_log_panel(mut contexts: EguiContexts, log: Res<CombatLog>, mut cmd: Commands) {
+    egui::Window::new("Combat Log")
+        .default_width(420.0)
+        .show(contexts.ctx_mut(), |ui| {
+            if ui.button("Export JSON").clicked() {
+                cmd.trigger(ExportCombatLog);
+            }
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for entry in log.entries.iter() {
+                        ui.label(entry.to_string());
+                    }
+                });
+        });
+}
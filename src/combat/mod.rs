@@ -0,0 +1,203 @@
+use avian3d::prelude::{CollidingEntities, Sensor};
+use bevy::{prelude::*, utils::hashbrown::HashSet};
+use log::CombatLogPlugin;
+
+use crate::{
+    health::{death::Dead, Health, HealthAffect},
+    rpg_data::{
+        core::CoreData,
+        dice::{DegreeOfSuccess, DiceRng},
+        turns::{TurnPhase, TurnTick},
+    },
+};
+
+pub mod log;
+
+/// AC used for targets without a character sheet
+pub const DEFAULT_AC: i32 = 10;
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(CombatLogPlugin);
+        app.register_type::<Faction>();
+        app.add_systems(FixedUpdate, process_hitboxes);
+        app.add_systems(
+            Update,
+            reset_attacks_on_turn_start.run_if(on_event::<TurnTick>),
+        );
+    }
+}
+
+/// Entities of the same faction can't hurt each other through hitboxes
+#[derive(Debug, Component, Reflect, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Faction {
+    Player,
+    Hostile,
+    Neutral,
+}
+
+/// A sensor volume that deals its `affect` to every hurtbox it overlaps, at most once per swing.
+///
+/// A hitbox without a faction hits everything, which is what environmental hazards want
+#[derive(Debug, Component, Clone)]
+#[require(Sensor, CollidingEntities)]
+pub struct Hitbox {
+    pub affect: HealthAffect,
+    pub faction: Option<Faction>,
+    /// Roll against the target's AC instead of always landing
+    pub strike: Option<Strike>,
+    pub active: bool,
+    struck: HashSet<Entity>,
+}
+
+impl Hitbox {
+    pub fn new(affect: HealthAffect, faction: Option<Faction>) -> Self {
+        Self {
+            affect,
+            faction,
+            strike: None,
+            active: true,
+            struck: HashSet::new(),
+        }
+    }
+
+    pub fn with_strike(mut self, strike: Strike) -> Self {
+        self.strike = Some(strike);
+        self
+    }
+}
+
+/// The attack roll side of a hitbox. A success deals the hitbox `affect`, a critical success deals `critical` instead
+#[derive(Debug, Clone)]
+pub struct Strike {
+    pub bonus: i32,
+    pub critical: HealthAffect,
+    /// +1 to hit every target after the first one
+    pub sweep: bool,
+}
+
+/// Attacks made since the start of the current turn, for the multiple attack penalty
+#[derive(Debug, Component, Clone, Copy, Default)]
+pub struct AttacksThisTurn(pub u32);
+
+/// PF2e multiple attack penalty for an attack made after `attacks` others this turn
+pub fn multiple_attack_penalty(attacks: u32, agile: bool) -> i32 {
+    match (attacks, agile) {
+        (0, _) => 0,
+        (1, false) => -5,
+        (1, true) => -4,
+        (_, false) => -10,
+        (_, true) => -8,
+    }
+}
+
+/// Marks a collider that hitboxes can strike. The damage goes to the first entity with `Health`, starting from the
+/// hurtbox itself and walking up its ancestors
+#[derive(Debug, Component, Clone, Copy, Default)]
+pub struct Hurtbox;
+
+/// Triggered whenever a hitbox strikes something
+#[derive(Debug, Event, Clone)]
+pub struct HitLanded {
+    pub hitbox: Entity,
+    pub attacker: Option<Entity>,
+    pub target: Entity,
+}
+
+/// Triggered whenever an attack roll is made against a target's AC
+#[derive(Debug, Event, Clone)]
+pub struct AttackRolled {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub natural: u32,
+    pub total: i32,
+    pub dc: i32,
+    pub degree: DegreeOfSuccess,
+}
+
+fn reset_attacks_on_turn_start(
+    mut events: EventReader<TurnTick>,
+    mut q: Query<&mut AttacksThisTurn>,
+) {
+    if !events.read().any(|tick| tick.phase == TurnPhase::Start) {
+        return;
+    }
+    for mut attacks in q.iter_mut() {
+        attacks.0 = 0;
+    }
+}
+
+fn process_hitboxes(
+    mut q_hitboxes: Query<(Entity, &mut Hitbox, &CollidingEntities)>,
+    q_hurtboxes: Query<(), With<Hurtbox>>,
+    // corpses keep their hurtboxes for a while but can't be struck anymore
+    q_targets: Query<(Option<&Faction>, Option<&CoreData>), (With<Health>, Without<Dead>)>,
+    parents: Query<&Parent>,
+    mut rng: ResMut<DiceRng>,
+    mut cmd: Commands,
+) {
+    for (hitbox_entity, mut hitbox, colliding) in q_hitboxes.iter_mut() {
+        if !hitbox.active {
+            continue;
+        }
+        for &other in colliding.iter() {
+            if !q_hurtboxes.contains(other) {
+                continue;
+            }
+            let Some(target) = std::iter::once(other)
+                .chain(parents.iter_ancestors(other))
+                .find(|e| q_targets.contains(*e))
+            else {
+                continue;
+            };
+            if hitbox.affect.source == Some(target) {
+                continue;
+            }
+            let Ok((faction, core)) = q_targets.get(target) else {
+                continue;
+            };
+            if hitbox.faction.is_some() && hitbox.faction == faction.copied() {
+                continue;
+            }
+            if hitbox.struck.contains(&target) {
+                continue;
+            }
+            let swept = !hitbox.struck.is_empty();
+            hitbox.struck.insert(target);
+
+            let affect = match &hitbox.strike {
+                None => hitbox.affect.clone(),
+                Some(strike) => {
+                    let dc = core.map_or(DEFAULT_AC, |c| c.ac.0 as i32);
+                    let sweep = if strike.sweep && swept { 1 } else { 0 };
+                    let natural = rng.d20();
+                    let total = natural as i32 + strike.bonus + sweep;
+                    let degree = DegreeOfSuccess::from_check(natural, total, dc);
+                    if let Some(attacker) = hitbox.affect.source {
+                        cmd.trigger(AttackRolled {
+                            attacker,
+                            target,
+                            natural,
+                            total,
+                            dc,
+                            degree,
+                        });
+                    }
+                    match degree {
+                        DegreeOfSuccess::CriticalSuccess => strike.critical.clone(),
+                        DegreeOfSuccess::Success => hitbox.affect.clone(),
+                        _ => continue,
+                    }
+                }
+            };
+            cmd.trigger_targets(affect, target);
+            cmd.trigger(HitLanded {
+                hitbox: hitbox_entity,
+                attacker: hitbox.affect.source,
+                target,
+            });
+        }
+    }
+}
@@ -9,9 +9,11 @@ impl Plugin for GameStatesPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.init_state::<PauseState>()
             .init_state::<MouseState>()
+            .enable_state_scoped_entities::<PauseState>()
             .add_systems(Update, toggle_paused)
             .add_systems(OnEnter(PauseState::Running), enter_running_state)
             .add_systems(OnEnter(PauseState::Paused), enter_pause_state)
+            .add_systems(OnEnter(PauseState::GameOver), enter_pause_state)
             .add_systems(OnEnter(MouseState::Captured), enter_mouse_captured)
             .add_systems(OnEnter(MouseState::Free), enter_mouse_free);
     }
@@ -22,6 +24,8 @@ pub enum PauseState {
     #[default]
     Running,
     Paused,
+    /// The player died, the game stays stopped until they respawn
+    GameOver,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, States)]
@@ -37,10 +41,11 @@ fn toggle_paused(
     mut next: ResMut<NextState<PauseState>>,
 ) {
     if keyboard.just_pressed(KeyCode::Escape) {
-        next.set(match current.get() {
-            PauseState::Running => PauseState::Paused,
-            PauseState::Paused => PauseState::Running,
-        });
+        match current.get() {
+            PauseState::Running => next.set(PauseState::Paused),
+            PauseState::Paused => next.set(PauseState::Running),
+            PauseState::GameOver => {}
+        }
     }
 }
 
@@ -0,0 +1,156 @@
+use bevy::{
+    prelude::*,
+    utils::hashbrown::{HashMap, HashSet},
+};
+use serde::{Deserialize, Serialize};
+
+/// Damage types following PF2e (remaster naming)
+#[derive(Debug, Hash, Reflect, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageType {
+    Bludgeoning,
+    Piercing,
+    Slashing,
+    Bleed,
+    Acid,
+    Cold,
+    Electricity,
+    Fire,
+    Force,
+    Sonic,
+    Vitality,
+    Void,
+    Mental,
+    Poison,
+    Spirit,
+}
+
+/// A single typed chunk of damage, a `HealthAffect` can carry any number of these (e.g. a flaming longsword deals slashing + fire)
+#[derive(Debug, Reflect, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DamageComponent {
+    pub amount: u32,
+    pub damage_type: DamageType,
+    #[serde(default)]
+    pub persistent: bool,
+}
+
+impl DamageComponent {
+    pub fn new(amount: u32, damage_type: DamageType) -> Self {
+        Self {
+            amount,
+            damage_type,
+            persistent: false,
+        }
+    }
+
+    pub fn persistent(amount: u32, damage_type: DamageType) -> Self {
+        Self {
+            amount,
+            damage_type,
+            persistent: true,
+        }
+    }
+}
+
+/// Immunities, weaknesses and resistances of an entity. Entities without this component take damage as-is
+#[derive(Debug, Component, Reflect, Clone, Default, Serialize, Deserialize)]
+pub struct DamageDefenses {
+    #[serde(default)]
+    pub immunities: HashSet<DamageType>,
+    #[serde(default)]
+    pub weaknesses: HashMap<DamageType, u32>,
+    #[serde(default)]
+    pub resistances: HashMap<DamageType, u32>,
+}
+
+impl DamageDefenses {
+    /// Resolves the final damage per type. Components of the same type (and persistence) are combined first so that
+    /// weaknesses and resistances only apply once per type, then immunities, weaknesses, and resistances are applied
+    /// in that order as per the PF2e rules.
+    pub fn apply(&self, damage: &[DamageComponent]) -> Vec<DamageComponent> {
+        let mut combined: Vec<DamageComponent> = Vec::new();
+        for component in damage {
+            if let Some(existing) = combined.iter_mut().find(|c| {
+                c.damage_type == component.damage_type && c.persistent == component.persistent
+            }) {
+                existing.amount += component.amount;
+            } else {
+                combined.push(component.clone());
+            }
+        }
+        for component in combined.iter_mut() {
+            if self.immunities.contains(&component.damage_type) {
+                component.amount = 0;
+                continue;
+            }
+            if component.amount > 0 {
+                if let Some(weakness) = self.weaknesses.get(&component.damage_type) {
+                    component.amount += weakness;
+                }
+            }
+            if let Some(resistance) = self.resistances.get(&component.damage_type) {
+                component.amount = component.amount.saturating_sub(*resistance);
+            }
+        }
+        combined
+    }
+}
+
+pub fn total_damage(damage: &[DamageComponent]) -> u32 {
+    damage.iter().map(|c| c.amount).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defenses(
+        immunities: &[DamageType],
+        weaknesses: &[(DamageType, u32)],
+        resistances: &[(DamageType, u32)],
+    ) -> DamageDefenses {
+        DamageDefenses {
+            immunities: immunities.iter().copied().collect(),
+            weaknesses: weaknesses.iter().copied().collect(),
+            resistances: resistances.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn immunity_ignores_weakness() {
+        let defenses = defenses(&[DamageType::Fire], &[(DamageType::Fire, 5)], &[]);
+        let damage = defenses.apply(&[DamageComponent::new(4, DamageType::Fire)]);
+        assert_eq!(total_damage(&damage), 0);
+    }
+
+    #[test]
+    fn resistance_stops_at_zero() {
+        let defenses = defenses(&[], &[], &[(DamageType::Cold, 10)]);
+        let damage = defenses.apply(&[DamageComponent::new(4, DamageType::Cold)]);
+        assert_eq!(total_damage(&damage), 0);
+    }
+
+    #[test]
+    fn weakness_applies_before_resistance() {
+        let defenses = defenses(&[], &[(DamageType::Acid, 3)], &[(DamageType::Acid, 5)]);
+        // (4 + 3) - 5, resisting first would floor at 0 and leave 3
+        let damage = defenses.apply(&[DamageComponent::new(4, DamageType::Acid)]);
+        assert_eq!(total_damage(&damage), 2);
+    }
+
+    #[test]
+    fn weakness_applies_once_per_type() {
+        let defenses = defenses(&[], &[(DamageType::Slashing, 2)], &[]);
+        let damage = defenses.apply(&[
+            DamageComponent::new(3, DamageType::Slashing),
+            DamageComponent::new(1, DamageType::Slashing),
+            DamageComponent::new(2, DamageType::Fire),
+        ]);
+        assert_eq!(
+            damage,
+            vec![
+                DamageComponent::new(6, DamageType::Slashing),
+                DamageComponent::new(2, DamageType::Fire),
+            ]
+        );
+    }
+}
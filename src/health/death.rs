@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use avian3d::prelude::{LockedAxes, RigidBody};
+use bevy::prelude::*;
+
+use crate::{
+    game_states::PauseState,
+    items::{inventory::ItemStack, ItemState, ItemType},
+};
+
+use super::{periodic::Regeneration, DieOnHealthZero, HealthDepleted};
+
+pub const DEFAULT_CORPSE_LIFETIME: Duration = Duration::from_secs(30);
+
+pub struct DeathPlugin;
+
+impl Plugin for DeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            despawn_expired_corpses.run_if(in_state(PauseState::Running)),
+        );
+        app.add_observer(die_on_health_depleted);
+        app.add_observer(handle_died);
+        app.add_observer(drop_loot_on_death);
+        app.add_observer(play_death_animation);
+        app.add_observer(ragdoll_on_death);
+    }
+}
+
+/// Triggered on an entity when it dies. Observe this to hook into the death of anything
+#[derive(Debug, Event, Clone)]
+pub struct Died {
+    pub killer: Option<Entity>,
+    pub final_damage: u32,
+}
+
+/// Sent whenever loot is dropped into the world, by dead entities or opened chests
+#[derive(Debug, Event, Clone)]
+pub struct LootDropped {
+    pub position: Vec3,
+    pub items: Vec<ItemStack>,
+}
+
+/// Marks an entity that has died and is now a corpse
+#[derive(Debug, Component)]
+pub struct Dead;
+
+/// How long the corpse of this entity sticks around before being despawned. Defaults to `DEFAULT_CORPSE_LIFETIME`
+#[derive(Debug, Component, Clone, Copy)]
+pub struct CorpseLifetime(pub Duration);
+
+#[derive(Debug, Component)]
+struct Corpse(Timer);
+
+/// Dead entities with this never become corpses and are never despawned, whatever owns them handles the death itself
+/// (the player goes to the game over screen)
+#[derive(Debug, Component)]
+pub struct KeepOnDeath;
+
+#[derive(Debug, Component, Clone, Default)]
+pub struct LootOnDeath(pub Vec<Handle<ItemType>>);
+
+/// The animation to play on the entity's `AnimationPlayer` (which is usually on a child scene) when it dies
+#[derive(Debug, Component, Clone, Copy)]
+pub struct DeathAnimation(pub AnimationNodeIndex);
+
+/// Releases the rigid body on death so the corpse falls over instead of standing in place
+#[derive(Debug, Component)]
+pub struct RagdollOnDeath;
+
+fn die_on_health_depleted(
+    trigger: Trigger<HealthDepleted>,
+    q: Query<Option<&Regeneration>, With<DieOnHealthZero>>,
+    mut cmd: Commands,
+) {
+    let Ok(regen) = q.get(trigger.entity()) else {
+        return;
+    };
+    if regen.is_some_and(|r| r.is_active()) {
+        return;
+    }
+    let event = trigger.event();
+    cmd.trigger_targets(
+        Died {
+            killer: event.source,
+            final_damage: event.damage,
+        },
+        trigger.entity(),
+    );
+}
+
+fn handle_died(
+    trigger: Trigger<Died>,
+    q: Query<(Option<&CorpseLifetime>, Has<KeepOnDeath>)>,
+    mut cmd: Commands,
+) {
+    let entity = trigger.entity();
+    let Ok((lifetime, keep)) = q.get(entity) else {
+        return;
+    };
+    info!(
+        "{entity} died (killer: {:?}, final damage: {})",
+        trigger.killer, trigger.final_damage
+    );
+    cmd.entity(entity).remove::<DieOnHealthZero>().insert(Dead);
+    if keep {
+        return;
+    }
+    let lifetime = lifetime.map_or(DEFAULT_CORPSE_LIFETIME, |l| l.0);
+    cmd.entity(entity)
+        .insert(Corpse(Timer::new(lifetime, TimerMode::Once)));
+}
+
+fn despawn_expired_corpses(
+    mut q: Query<(Entity, &mut Corpse)>,
+    time: Res<Time>,
+    mut cmd: Commands,
+) {
+    for (e, mut corpse) in q.iter_mut() {
+        if corpse.0.tick(time.delta()).finished() {
+            cmd.entity(e).despawn_recursive();
+        }
+    }
+}
+
+fn drop_loot_on_death(
+    trigger: Trigger<Died>,
+    q: Query<(&LootOnDeath, &GlobalTransform)>,
+    mut cmd: Commands,
+) {
+    let Ok((loot, transform)) = q.get(trigger.entity()) else {
+        return;
+    };
+    if loot.0.is_empty() {
+        return;
+    }
+    cmd.trigger(LootDropped {
+        position: transform.translation(),
+        items: loot
+            .0
+            .iter()
+            .map(|item| ItemStack {
+                item: item.clone(),
+                state: ItemState::default(),
+                count: 1,
+            })
+            .collect(),
+    });
+}
+
+fn play_death_animation(
+    trigger: Trigger<Died>,
+    q_death_anim: Query<&DeathAnimation>,
+    children: Query<&Children>,
+    mut q_players: Query<(&mut AnimationPlayer, Option<&mut AnimationTransitions>)>,
+) {
+    let Ok(anim) = q_death_anim.get(trigger.entity()) else {
+        return;
+    };
+    for child in children.iter_descendants(trigger.entity()) {
+        let Ok((mut player, transitions)) = q_players.get_mut(child) else {
+            continue;
+        };
+        match transitions {
+            Some(mut transitions) => {
+                transitions.play(&mut player, anim.0, Duration::from_secs_f32(0.2));
+            }
+            None => {
+                player.play(anim.0);
+            }
+        }
+        return;
+    }
+}
+
+fn ragdoll_on_death(trigger: Trigger<Died>, q: Query<(), With<RagdollOnDeath>>, mut cmd: Commands) {
+    if !q.contains(trigger.entity()) {
+        return;
+    }
+    cmd.entity(trigger.entity())
+        .insert((RigidBody::Dynamic, LockedAxes::new()));
+}
@@ -0,0 +1,263 @@
+use std::time::Duration;
+
+use bevy::{color::palettes::css, prelude::*};
+use bevy_tween::{
+    combinator::{event, sequence, tween},
+    prelude::*,
+    tween::AnimationTarget,
+    tween_event::TweenEventPlugin,
+};
+
+use crate::{player::MainCamera, settings::GameSettings};
+
+use super::{damage::DamageType, Health, HealthAffectApplied};
+
+const FLOATING_NUMBER_HEIGHT: f32 = 1.5;
+const FLOATING_NUMBER_RISE: f32 = 1.0;
+const FLOATING_NUMBER_SPACING: f32 = 0.4;
+const HEALTH_BAR_WIDTH: f32 = 80.0;
+const HEALTH_BAR_HEIGHT: f32 = 8.0;
+
+pub struct HealthDisplayPlugin;
+
+impl Plugin for HealthDisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(TweenEventPlugin::<FloatingNumberTweenEvent>::default());
+        app.add_systems(
+            Update,
+            (
+                follow_world_anchors,
+                update_health_bars,
+                floating_number_events,
+            ),
+        );
+        app.add_observer(spawn_floating_numbers);
+        app.add_observer(spawn_health_bar);
+    }
+}
+
+/// Opt-in marker to show a health bar over the entity, `height` above its origin
+#[derive(Debug, Component, Clone, Copy)]
+pub struct ShowHealthBar {
+    pub height: f32,
+}
+
+impl Default for ShowHealthBar {
+    fn default() -> Self {
+        Self { height: 2.0 }
+    }
+}
+
+/// Keeps a UI node over an entity in the world. The node is despawned along with its anchor
+#[derive(Component)]
+struct WorldAnchored {
+    anchor: Entity,
+    offset: Vec3,
+}
+
+#[derive(Component)]
+struct FloatingNumber {
+    label: Entity,
+}
+
+#[derive(Component)]
+struct HealthBarFill {
+    owner: Entity,
+}
+
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+enum FloatingNumberTweenEvent {
+    #[default]
+    None,
+    End(Entity),
+}
+
+fn damage_color(damage_type: DamageType) -> Color {
+    match damage_type {
+        DamageType::Bludgeoning | DamageType::Piercing | DamageType::Slashing => css::ANTIQUE_WHITE,
+        DamageType::Bleed => css::CRIMSON,
+        DamageType::Acid => css::CHARTREUSE,
+        DamageType::Cold => css::LIGHT_BLUE,
+        DamageType::Electricity => css::YELLOW,
+        DamageType::Fire => css::ORANGE_RED,
+        DamageType::Force => css::MEDIUM_PURPLE,
+        DamageType::Sonic => css::LIGHT_CYAN,
+        DamageType::Vitality => css::GOLD,
+        DamageType::Void => css::DARK_SLATE_BLUE,
+        DamageType::Mental => css::VIOLET,
+        DamageType::Poison => css::OLIVE_DRAB,
+        DamageType::Spirit => css::LAVENDER,
+    }
+    .into()
+}
+
+fn spawn_floating_numbers(
+    trigger: Trigger<HealthAffectApplied>,
+    q: Query<&GlobalTransform>,
+    mut cmd: Commands,
+    assets: Res<AssetServer>,
+    settings: Res<GameSettings>,
+) {
+    let Ok(transform) = q.get(trigger.entity()) else {
+        return;
+    };
+    let mut numbers: Vec<(String, Color)> = trigger
+        .damage
+        .iter()
+        .filter(|c| c.amount > 0)
+        .map(|c| (c.amount.to_string(), damage_color(c.damage_type)))
+        .collect();
+    if trigger.healing > 0 {
+        numbers.push((format!("+{}", trigger.healing), css::LIME.into()));
+    }
+    let (font, font_size) = if trigger.critical {
+        (settings.font.bold.clone(), 36.0)
+    } else {
+        (settings.font.regular.clone(), 24.0)
+    };
+    let origin = transform.translation() + Vec3::Y * FLOATING_NUMBER_HEIGHT;
+    let centre = (numbers.len() as f32 - 1.0) / 2.0;
+
+    for (i, (text, color)) in numbers.into_iter().enumerate() {
+        let start = origin + Vec3::X * (i as f32 - centre) * FLOATING_NUMBER_SPACING;
+        let end = start + Vec3::Y * FLOATING_NUMBER_RISE;
+        let text = if trigger.critical {
+            format!("{text}!")
+        } else {
+            text
+        };
+        let label = cmd
+            .spawn((
+                Text::new(text),
+                TextFont {
+                    font: assets.load(font.clone()),
+                    font_size,
+                    ..default()
+                },
+                TextColor(color),
+                Node {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                Visibility::Hidden,
+            ))
+            .id();
+
+        let target = AnimationTarget.into_target();
+        let mut translate_tween = target.transform_state(Transform::from_translation(start));
+        let mut anchor = cmd.spawn((
+            Name::new("Floating Number"),
+            FloatingNumber { label },
+            Transform::from_translation(start),
+            AnimationTarget,
+        ));
+        let anchor_id = anchor.id();
+        anchor.animation().insert(sequence((
+            tween(
+                Duration::from_secs_f32(0.8),
+                EaseKind::CubicOut,
+                translate_tween.translation_to(end),
+            ),
+            event(FloatingNumberTweenEvent::End(anchor_id)),
+        )));
+        cmd.entity(label).insert(WorldAnchored {
+            anchor: anchor_id,
+            offset: Vec3::ZERO,
+        });
+    }
+}
+
+fn floating_number_events(
+    mut events: EventReader<TweenEvent<FloatingNumberTweenEvent>>,
+    q: Query<&FloatingNumber>,
+    mut cmd: Commands,
+) {
+    for event in events.read() {
+        let FloatingNumberTweenEvent::End(entity) = event.data else {
+            continue;
+        };
+        if let Ok(number) = q.get(entity) {
+            cmd.entity(number.label).despawn_recursive();
+        }
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+
+fn spawn_health_bar(
+    trigger: Trigger<OnAdd, ShowHealthBar>,
+    q: Query<&ShowHealthBar>,
+    mut cmd: Commands,
+) {
+    let owner = trigger.entity();
+    let Ok(bar) = q.get(owner) else {
+        return;
+    };
+    cmd.spawn((
+        Name::new("Health Bar"),
+        WorldAnchored {
+            anchor: owner,
+            offset: Vec3::Y * bar.height,
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(HEALTH_BAR_WIDTH),
+            height: Val::Px(HEALTH_BAR_HEIGHT),
+            margin: UiRect::left(Val::Px(-HEALTH_BAR_WIDTH / 2.0)),
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        BackgroundColor(css::BLACK.with_alpha(0.6).into()),
+        BorderColor(css::BLACK.into()),
+        Visibility::Hidden,
+    ))
+    .with_children(|cmd| {
+        cmd.spawn((
+            HealthBarFill { owner },
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(css::DARK_RED.into()),
+        ));
+    });
+}
+
+fn update_health_bars(mut q_fill: Query<(&HealthBarFill, &mut Node)>, q_health: Query<&Health>) {
+    for (fill, mut node) in q_fill.iter_mut() {
+        let Ok(hp) = q_health.get(fill.owner) else {
+            continue;
+        };
+        let percent = if hp.max == 0 {
+            0.0
+        } else {
+            hp.current as f32 / hp.max as f32 * 100.0
+        };
+        node.width = Val::Percent(percent);
+    }
+}
+
+fn follow_world_anchors(
+    mut q_nodes: Query<(Entity, &WorldAnchored, &mut Node, &mut Visibility)>,
+    q_anchors: Query<&GlobalTransform>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut cmd: Commands,
+) {
+    let Ok((camera, camera_transform)) = q_camera.get_single() else {
+        return;
+    };
+    for (e, anchored, mut node, mut visibility) in q_nodes.iter_mut() {
+        let Ok(anchor) = q_anchors.get(anchored.anchor) else {
+            cmd.entity(e).despawn_recursive();
+            continue;
+        };
+        match camera.world_to_viewport(camera_transform, anchor.translation() + anchored.offset) {
+            Ok(position) => {
+                node.left = Val::Px(position.x);
+                node.top = Val::Px(position.y);
+                *visibility = Visibility::Inherited;
+            }
+            Err(_) => *visibility = Visibility::Hidden,
+        }
+    }
+}
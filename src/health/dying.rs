@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+
+use crate::rpg_data::{
+    conditions::ConditionChanged,
+    dice::{DegreeOfSuccess, DiceRng},
+    turns::{TurnPhase, TurnTick},
+};
+
+use super::{
+    death::{Dead, Died},
+    periodic::Regeneration,
+    HealthDepleted, HealthRestored,
+};
+
+/// At this dying value the character dies
+pub const DYING_DEATH_THRESHOLD: u32 = 4;
+/// Recovery checks are flat checks against this + the current dying value
+pub const RECOVERY_CHECK_BASE_DC: i32 = 10;
+
+pub struct DyingPlugin;
+
+impl Plugin for DyingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, recovery_checks.run_if(on_event::<TurnTick>));
+        app.add_observer(enter_dying);
+        app.add_observer(leave_dying_on_healed);
+    }
+}
+
+/// Entities with this go through the PF2e Dying/Wounded rules at 0 HP instead of dying outright
+#[derive(Debug, Component)]
+pub struct UsesDyingRules;
+
+#[derive(Debug, Component, Clone, Copy)]
+pub struct Dying(pub u32);
+
+#[derive(Debug, Component, Clone, Copy, Default)]
+pub struct Wounded(pub u32);
+
+#[derive(Debug, Component)]
+pub struct Unconscious;
+
+/// Active regeneration keeps the dying value from ever reaching death
+fn dying_cap(regen: Option<&Regeneration>) -> u32 {
+    if regen.is_some_and(|r| r.is_active()) {
+        DYING_DEATH_THRESHOLD - 1
+    } else {
+        u32::MAX
+    }
+}
+
+fn enter_dying(
+    trigger: Trigger<HealthDepleted>,
+    mut q: Query<
+        (Option<&mut Dying>, Option<&Wounded>, Option<&Regeneration>),
+        With<UsesDyingRules>,
+    >,
+    mut cmd: Commands,
+) {
+    let entity = trigger.entity();
+    let Ok((dying, wounded, regen)) = q.get_mut(entity) else {
+        return;
+    };
+    let increase = if trigger.critical { 2 } else { 1 };
+    let cap = dying_cap(regen);
+    let value = match dying {
+        Some(mut dying) => {
+            dying.0 = (dying.0 + increase).min(cap);
+            dying.0
+        }
+        None => {
+            let value = (increase + wounded.map_or(0, |w| w.0)).min(cap);
+            cmd.entity(entity).insert((Dying(value), Unconscious));
+            cmd.trigger_targets(ConditionChanged::gained("unconscious", None), entity);
+            value
+        }
+    };
+    info!("{entity} is dying ({value})");
+    cmd.trigger_targets(ConditionChanged::gained("dying", Some(value)), entity);
+    if value >= DYING_DEATH_THRESHOLD {
+        cmd.trigger_targets(
+            Died {
+                killer: trigger.source,
+                final_damage: trigger.damage,
+            },
+            entity,
+        );
+    }
+}
+
+fn leave_dying_on_healed(
+    trigger: Trigger<HealthRestored>,
+    q: Query<(Option<&Dying>, Option<&Wounded>, Has<Unconscious>), With<UsesDyingRules>>,
+    mut cmd: Commands,
+) {
+    let entity = trigger.entity();
+    let Ok((dying, wounded, unconscious)) = q.get(entity) else {
+        return;
+    };
+    if dying.is_some() {
+        let wounded = wounded.map_or(0, |w| w.0) + 1;
+        cmd.entity(entity).insert(Wounded(wounded));
+        cmd.trigger_targets(ConditionChanged::lost("dying"), entity);
+        cmd.trigger_targets(ConditionChanged::gained("wounded", Some(wounded)), entity);
+    }
+    if unconscious {
+        cmd.trigger_targets(ConditionChanged::lost("unconscious"), entity);
+    }
+    cmd.entity(entity).remove::<(Dying, Unconscious)>();
+}
+
+fn recovery_checks(
+    mut events: EventReader<TurnTick>,
+    mut q: Query<(Entity, &mut Dying, Option<&Wounded>, Option<&Regeneration>), Without<Dead>>,
+    mut rng: ResMut<DiceRng>,
+    mut cmd: Commands,
+) {
+    for tick in events.read() {
+        if tick.phase != TurnPhase::Start {
+            continue;
+        }
+        for (entity, mut dying, wounded, regen) in q.iter_mut() {
+            let degree = rng.flat_check(RECOVERY_CHECK_BASE_DC + dying.0 as i32);
+            dying.0 = match degree {
+                DegreeOfSuccess::CriticalSuccess => dying.0.saturating_sub(2),
+                DegreeOfSuccess::Success => dying.0.saturating_sub(1),
+                DegreeOfSuccess::Failure => dying.0 + 1,
+                DegreeOfSuccess::CriticalFailure => dying.0 + 2,
+            }
+            .min(dying_cap(regen));
+            info!("{entity} recovery check: {degree:?}, dying {}", dying.0);
+            if dying.0 == 0 {
+                // stable, but still unconscious at 0 HP until healed
+                let wounded = wounded.map_or(0, |w| w.0) + 1;
+                cmd.entity(entity)
+                    .remove::<Dying>()
+                    .insert(Wounded(wounded));
+                cmd.trigger_targets(ConditionChanged::lost("dying"), entity);
+                cmd.trigger_targets(ConditionChanged::gained("wounded", Some(wounded)), entity);
+                continue;
+            }
+            cmd.trigger_targets(ConditionChanged::gained("dying", Some(dying.0)), entity);
+            if dying.0 >= DYING_DEATH_THRESHOLD {
+                cmd.trigger_targets(
+                    Died {
+                        killer: None,
+                        final_damage: 0,
+                    },
+                    entity,
+                );
+            }
+        }
+    }
+}
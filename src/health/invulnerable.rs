@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::game_states::PauseState;
+
+pub struct InvulnerabilityPlugin;
+
+impl Plugin for InvulnerabilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            tick_invulnerability.run_if(in_state(PauseState::Running)),
+        );
+        app.add_observer(grant_invulnerability);
+    }
+}
+
+/// While present, damage from any `HealthAffect` that isn't `unavoidable` is ignored. Healing still goes through
+#[derive(Debug, Component, Clone)]
+pub struct Invulnerable(pub Timer);
+
+/// Trigger on an entity to make it invulnerable for a while. If it already is, the longer of the two windows is kept
+#[derive(Debug, Event, Clone, Copy)]
+pub struct GrantInvulnerability(pub Duration);
+
+fn grant_invulnerability(
+    trigger: Trigger<GrantInvulnerability>,
+    mut q: Query<Option<&mut Invulnerable>>,
+    mut cmd: Commands,
+) {
+    let Ok(current) = q.get_mut(trigger.entity()) else {
+        return;
+    };
+    match current {
+        Some(current) if current.0.remaining() >= trigger.0 => (),
+        Some(mut current) => current.0 = Timer::new(trigger.0, TimerMode::Once),
+        None => {
+            cmd.entity(trigger.entity())
+                .insert(Invulnerable(Timer::new(trigger.0, TimerMode::Once)));
+        }
+    }
+}
+
+fn tick_invulnerability(
+    mut q: Query<(Entity, &mut Invulnerable)>,
+    time: Res<Time>,
+    mut cmd: Commands,
+) {
+    for (e, mut invulnerable) in q.iter_mut() {
+        if invulnerable.0.tick(time.delta()).finished() {
+            cmd.entity(e).remove::<Invulnerable>();
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::time::Duration;
+
+use bevy::{ecs::component::StorageType, prelude::*};
+use damage::{total_damage, DamageComponent, DamageDefenses, DamageType};
+use death::{Dead, DeathPlugin};
+use display::HealthDisplayPlugin;
+use dying::DyingPlugin;
+use invulnerable::{InvulnerabilityPlugin, Invulnerable};
+use periodic::{GainPersistentDamage, PeriodicHealthPlugin};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game_states::PauseState;
+
+pub mod damage;
+pub mod death;
+pub mod display;
+pub mod dying;
+pub mod invulnerable;
+pub mod periodic;
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DamageDefenses>();
+        app.add_plugins((
+            DeathPlugin,
+            DyingPlugin,
+            PeriodicHealthPlugin,
+            InvulnerabilityPlugin,
+            HealthDisplayPlugin,
+        ));
+        app.add_systems(
+            Update,
+            expire_temporary_hp.run_if(in_state(PauseState::Running)),
+        );
+    }
+}
+
+fn handle_health_affects(
+    trigger: Trigger<HealthAffect>,
+    mut q_health: Query<(&mut Health, Option<&DamageDefenses>, Has<Invulnerable>), Without<Dead>>,
+    mut cmd: Commands,
+) {
+    let entity = trigger.entity();
+    let Ok((mut hp, defenses, invulnerable)) = q_health.get_mut(entity) else {
+        return;
+    };
+    let affect = trigger.event();
+    let ignores_damage = invulnerable && !affect.unavoidable;
+    let (persistent, immediate): (Vec<_>, Vec<_>) = affect
+        .damage
+        .iter()
+        .filter(|_| !ignores_damage)
+        .cloned()
+        .partition(|c| c.persistent);
+    if !persistent.is_empty() {
+        cmd.trigger_targets(GainPersistentDamage(persistent), entity);
+    }
+    let resolved = match defenses {
+        Some(defenses) => defenses.apply(&immediate),
+        None => immediate,
+    };
+    let total = total_damage(&resolved);
+    let damage = hp.absorb_with_temporary(total);
+    let previous = hp.current;
+    let after_damage = previous.saturating_sub(damage);
+    hp.current = after_damage.saturating_add(affect.healing).min(hp.max);
+    if let Some(temp) = &affect.temp_hp {
+        hp.grant_temporary(temp.clone());
+    }
+    cmd.trigger_targets(
+        HealthAffectApplied {
+            source: affect.source,
+            damage: resolved,
+            absorbed: total - damage,
+            healing: hp.current - after_damage,
+            critical: affect.critical,
+        },
+        entity,
+    );
+
+    if damage > 0 && hp.current == 0 {
+        cmd.trigger_targets(
+            HealthDepleted {
+                source: affect.source,
+                damage,
+                critical: affect.critical,
+            },
+            entity,
+        );
+    } else if previous == 0 && hp.current > 0 {
+        cmd.trigger_targets(HealthRestored, entity);
+    }
+}
+
+/// Damage and/or healing applied to a single entity. Damage is resolved against the target's `DamageDefenses` before healing is added
+#[derive(Debug, Event, Clone, Default)]
+pub struct HealthAffect {
+    pub damage: Vec<DamageComponent>,
+    pub healing: u32,
+    /// Temporary HP granted by this affect, applied after damage and healing
+    pub temp_hp: Option<TemporaryHp>,
+    /// The entity responsible for this affect, if any
+    pub source: Option<Entity>,
+    pub critical: bool,
+    /// Unavoidable damage (usually environmental) goes through `Invulnerable`
+    pub unavoidable: bool,
+}
+
+impl HealthAffect {
+    pub fn damage(amount: u32, damage_type: DamageType) -> Self {
+        Self {
+            damage: vec![DamageComponent::new(amount, damage_type)],
+            ..default()
+        }
+    }
+
+    pub fn healing(amount: u32) -> Self {
+        Self {
+            healing: amount,
+            ..default()
+        }
+    }
+
+    pub fn temporary(temp_hp: TemporaryHp) -> Self {
+        Self {
+            temp_hp: Some(temp_hp),
+            ..default()
+        }
+    }
+
+    pub fn with_damage(mut self, component: DamageComponent) -> Self {
+        self.damage.push(component);
+        self
+    }
+
+    pub fn with_source(mut self, source: Entity) -> Self {
+        self.source = Some(source);
+        self
+    }
+}
+
+/// Triggered on an entity once a `HealthAffect` has been resolved, with the amounts that actually applied
+#[derive(Debug, Event, Clone)]
+pub struct HealthAffectApplied {
+    pub source: Option<Entity>,
+    /// Damage per type after immunities, weaknesses, and resistances
+    pub damage: Vec<DamageComponent>,
+    /// How much of the damage was soaked up by temporary HP
+    pub absorbed: u32,
+    pub healing: u32,
+    pub critical: bool,
+}
+
+/// Triggered on an entity whenever damage leaves it at 0 HP, including damage taken while already at 0
+#[derive(Debug, Event, Clone)]
+pub struct HealthDepleted {
+    pub source: Option<Entity>,
+    pub damage: u32,
+    pub critical: bool,
+}
+
+/// Triggered on an entity when it is healed back up from 0 HP
+#[derive(Debug, Event, Clone)]
+pub struct HealthRestored;
+
+/// The one health model for both the ECS and serialized character data (see `rpg_data::sheet` for keeping them in sync)
+#[derive(Debug, Default, Clone, Reflect, Serialize, Deserialize)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+    #[serde(skip)]
+    pub temp: Option<TemporaryHp>,
+}
+
+impl Health {
+    /// Temporary HP don't stack, the highest value is kept
+    pub fn grant_temporary(&mut self, temp: TemporaryHp) {
+        if self.temp.as_ref().map_or(0, |t| t.amount) < temp.amount {
+            self.temp = Some(temp);
+        }
+    }
+
+    /// Removes as much of `damage` as possible from the temporary HP, returning whatever is left over
+    pub fn absorb_with_temporary(&mut self, damage: u32) -> u32 {
+        let Some(temp) = &mut self.temp else {
+            return damage;
+        };
+        let absorbed = temp.amount.min(damage);
+        temp.amount -= absorbed;
+        if temp.amount == 0 {
+            self.temp = None;
+        }
+        damage - absorbed
+    }
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct TemporaryHp {
+    pub amount: u32,
+    pub expires: Option<Timer>,
+}
+
+impl TemporaryHp {
+    pub fn new(amount: u32) -> Self {
+        Self {
+            amount,
+            expires: None,
+        }
+    }
+
+    pub fn with_duration(amount: u32, duration: Duration) -> Self {
+        Self {
+            amount,
+            expires: Some(Timer::new(duration, TimerMode::Once)),
+        }
+    }
+}
+
+fn expire_temporary_hp(mut q: Query<&mut Health>, time: Res<Time>) {
+    for mut hp in q.iter_mut() {
+        // ticking the timer shouldn't count as a change to the health itself
+        let Some(timer) = hp
+            .bypass_change_detection()
+            .temp
+            .as_mut()
+            .and_then(|t| t.expires.as_mut())
+        else {
+            continue;
+        };
+        if timer.tick(time.delta()).finished() {
+            hp.temp = None;
+        }
+    }
+}
+
+impl Component for Health {
+    const STORAGE_TYPE: StorageType = StorageType::SparseSet;
+    fn register_component_hooks(_hooks: &mut bevy::ecs::component::ComponentHooks) {
+        _hooks.on_add(|mut world, entity, _| {
+            world
+                .commands()
+                .entity(entity)
+                .observe(handle_health_affects);
+        });
+    }
+}
+
+#[derive(Debug, Component)]
+#[require(Health)]
+pub struct DieOnHealthZero;
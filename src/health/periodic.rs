@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+
+use crate::rpg_data::{
+    dice::{DegreeOfSuccess, DiceRng},
+    turns::{TurnClock, TurnPhase, TurnTick},
+};
+
+use super::{
+    damage::{DamageComponent, DamageType},
+    death::Dead,
+    HealthAffect, HealthAffectApplied,
+};
+
+/// Flat check DC to end persistent damage after it is dealt
+pub const PERSISTENT_DAMAGE_RECOVERY_DC: i32 = 15;
+
+pub struct PeriodicHealthPlugin;
+
+impl Plugin for PeriodicHealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<FastHealing>();
+        app.register_type::<Regeneration>();
+        app.add_systems(
+            Update,
+            (tick_healing_effects, tick_persistent_damage).run_if(on_event::<TurnTick>),
+        );
+        app.add_observer(gain_persistent_damage);
+        app.add_observer(deactivate_regeneration);
+    }
+}
+
+/// Heals a flat amount at the start of every turn
+#[derive(Debug, Component, Reflect, Clone, Copy)]
+pub struct FastHealing(pub u32);
+
+/// Heals a flat amount at the start of every turn unless deactivated. Taking damage of one of the `deactivated_by` types
+/// shuts it off until the end of the next turn. While active, the entity can't die from losing HP
+#[derive(Debug, Component, Reflect, Clone)]
+pub struct Regeneration {
+    pub amount: u32,
+    pub deactivated_by: Vec<DamageType>,
+    pub deactivated_until: Option<u64>,
+}
+
+impl Regeneration {
+    pub fn new(amount: u32, deactivated_by: Vec<DamageType>) -> Self {
+        Self {
+            amount,
+            deactivated_by,
+            deactivated_until: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.deactivated_until.is_none()
+    }
+}
+
+/// Persistent damage currently affecting an entity, dealt at the end of every turn until a recovery check succeeds.
+/// Persistent damage of the same type doesn't stack, only the highest amount is kept
+#[derive(Debug, Component, Clone, Default)]
+pub struct PersistentDamage(pub Vec<DamageComponent>);
+
+/// Triggered by `handle_health_affects` with the persistent components of a `HealthAffect`
+#[derive(Debug, Event, Clone)]
+pub(super) struct GainPersistentDamage(pub Vec<DamageComponent>);
+
+fn gain_persistent_damage(
+    trigger: Trigger<GainPersistentDamage>,
+    mut q: Query<Option<&mut PersistentDamage>, Without<Dead>>,
+    mut cmd: Commands,
+) {
+    let Ok(existing) = q.get_mut(trigger.entity()) else {
+        return;
+    };
+    let Some(mut existing) = existing else {
+        cmd.entity(trigger.entity())
+            .insert(PersistentDamage(trigger.0.clone()));
+        return;
+    };
+    for gained in trigger.0.iter() {
+        match existing
+            .0
+            .iter_mut()
+            .find(|c| c.damage_type == gained.damage_type)
+        {
+            Some(current) => current.amount = current.amount.max(gained.amount),
+            None => existing.0.push(gained.clone()),
+        }
+    }
+}
+
+/// Uses the resolved damage so damage negated by immunities or invulnerability doesn't shut regeneration off
+fn deactivate_regeneration(
+    trigger: Trigger<HealthAffectApplied>,
+    mut q: Query<&mut Regeneration>,
+    clock: Res<TurnClock>,
+) {
+    let Ok(mut regen) = q.get_mut(trigger.entity()) else {
+        return;
+    };
+    let deactivates = trigger
+        .damage
+        .iter()
+        .any(|c| c.amount > 0 && regen.deactivated_by.contains(&c.damage_type));
+    if deactivates {
+        regen.deactivated_until = Some(clock.turn + 1);
+    }
+}
+
+fn tick_healing_effects(
+    mut events: EventReader<TurnTick>,
+    q_fast_healing: Query<(Entity, &FastHealing), Without<Dead>>,
+    mut q_regen: Query<(Entity, &mut Regeneration), Without<Dead>>,
+    mut cmd: Commands,
+) {
+    for tick in events.read() {
+        match tick.phase {
+            TurnPhase::Start => {
+                for (e, fast_healing) in q_fast_healing.iter() {
+                    cmd.trigger_targets(HealthAffect::healing(fast_healing.0), e);
+                }
+                for (e, regen) in q_regen.iter() {
+                    if regen.is_active() {
+                        cmd.trigger_targets(HealthAffect::healing(regen.amount), e);
+                    }
+                }
+            }
+            TurnPhase::End => {
+                for (_, mut regen) in q_regen.iter_mut() {
+                    if regen
+                        .deactivated_until
+                        .is_some_and(|turn| turn <= tick.turn)
+                    {
+                        regen.deactivated_until = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn tick_persistent_damage(
+    mut events: EventReader<TurnTick>,
+    mut q: Query<(Entity, &mut PersistentDamage), Without<Dead>>,
+    mut rng: ResMut<DiceRng>,
+    mut cmd: Commands,
+) {
+    for tick in events.read() {
+        if tick.phase != TurnPhase::End {
+            continue;
+        }
+        for (e, mut persistent) in q.iter_mut() {
+            // dealt as regular damage so it isn't registered as persistent again
+            let affect = HealthAffect {
+                damage: persistent
+                    .0
+                    .iter()
+                    .map(|c| DamageComponent::new(c.amount, c.damage_type))
+                    .collect(),
+                ..default()
+            };
+            cmd.trigger_targets(affect, e);
+            persistent.0.retain(|_| {
+                rng.flat_check(PERSISTENT_DAMAGE_RECOVERY_DC) < DegreeOfSuccess::Success
+            });
+            if persistent.0.is_empty() {
+                cmd.entity(e).remove::<PersistentDamage>();
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_states::PauseState,
+    health::{HealthAffect, TemporaryHp},
+    rpg_data::{
+        buffs::{Buff, GrantBuff},
+        conditions::ConditionChanged,
+        core::{CoreData, SECONDS_PER_ACTION},
+        dice::DiceRng,
+    },
+    toast::ToastEvent,
+};
+
+use super::{
+    inventory::Inventory,
+    registry::{ItemId, ItemRegistry},
+    ConsumableItem, Item, ItemType,
+};
+
+pub struct ConsumablePlugin;
+
+impl Plugin for ConsumablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            tick_activations.run_if(in_state(PauseState::Running)),
+        );
+        app.add_observer(use_consumable);
+    }
+}
+
+#[derive(Debug, Reflect, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConsumableEffect {
+    /// Heals `dice`d`sides` + `bonus`
+    Heal {
+        dice: u32,
+        sides: u32,
+        #[serde(default)]
+        bonus: u32,
+    },
+    TemporaryHp {
+        amount: u32,
+        /// In seconds, lasts until used up if not set
+        #[serde(default)]
+        duration: Option<f32>,
+    },
+    AddCondition(String),
+    RemoveCondition(String),
+    Buff(Buff),
+}
+
+/// Trigger on an entity to use one of the consumables in its `Inventory`
+#[derive(Debug, Event, Clone)]
+pub struct UseConsumable(pub ItemId);
+
+/// Triggered on the user once a consumable is taken out of the inventory, this is the hook for animations
+#[derive(Debug, Event, Clone)]
+pub struct ConsumableActivated {
+    pub item: ItemId,
+    pub animation: Option<String>,
+    /// How long until the effects apply
+    pub duration: Duration,
+}
+
+/// Present while an entity is activating a consumable
+#[derive(Debug, Component)]
+pub struct Activating {
+    item: ConsumableItem,
+    timer: Timer,
+}
+
+fn use_consumable(
+    trigger: Trigger<UseConsumable>,
+    mut q: Query<(&mut Inventory, Has<Activating>)>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let e = trigger.entity();
+    let Ok((mut inventory, activating)) = q.get_mut(e) else {
+        return;
+    };
+    if activating {
+        return;
+    }
+    let Some(handle) = registry.get(&trigger.0) else {
+        registry.check_reference(&trigger.0, &format!("Use request for {e}"), &mut cmd);
+        return;
+    };
+    let Some(ItemType::Consumable(consumable)) = items.get(handle.id()) else {
+        warn!("'{}' is not a consumable", trigger.0);
+        return;
+    };
+    if !inventory.remove(handle, 1) {
+        cmd.trigger(ToastEvent(format!("No {} left", consumable.get_name())));
+        return;
+    }
+    let duration = Duration::from_secs_f32(consumable.actions as f32 * SECONDS_PER_ACTION);
+    cmd.entity(e).insert(Activating {
+        item: consumable.clone(),
+        timer: Timer::new(duration, TimerMode::Once),
+    });
+    cmd.trigger_targets(
+        ConsumableActivated {
+            item: trigger.0.clone(),
+            animation: consumable.animation.clone(),
+            duration,
+        },
+        e,
+    );
+}
+
+fn tick_activations(
+    mut q: Query<(Entity, &mut Activating, Option<&mut CoreData>)>,
+    time: Res<Time>,
+    mut rng: ResMut<DiceRng>,
+    mut cmd: Commands,
+) {
+    for (e, mut activating, mut core) in q.iter_mut() {
+        if !activating.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        cmd.entity(e).remove::<Activating>();
+        for effect in activating.item.effects.iter() {
+            apply_effect(effect, e, core.as_deref_mut(), &mut rng, &mut cmd);
+        }
+    }
+}
+
+fn apply_effect(
+    effect: &ConsumableEffect,
+    e: Entity,
+    core: Option<&mut CoreData>,
+    rng: &mut DiceRng,
+    cmd: &mut Commands,
+) {
+    match effect {
+        ConsumableEffect::Heal { dice, sides, bonus } => {
+            let amount = rng.roll_dice(*dice, *sides) + bonus;
+            cmd.trigger_targets(HealthAffect::healing(amount).with_source(e), e);
+        }
+        ConsumableEffect::TemporaryHp { amount, duration } => {
+            let temp_hp = match duration {
+                Some(secs) => {
+                    let Ok(duration) = Duration::try_from_secs_f32(*secs) else {
+                        warn!("Invalid temporary HP duration {secs}");
+                        return;
+                    };
+                    TemporaryHp::with_duration(*amount, duration)
+                }
+                None => TemporaryHp::new(*amount),
+            };
+            cmd.trigger_targets(HealthAffect::temporary(temp_hp).with_source(e), e);
+        }
+        ConsumableEffect::AddCondition(condition) => {
+            let Some(core) = core else {
+                return;
+            };
+            if !core.conditions.contains(condition) {
+                core.conditions.push(condition.clone());
+                cmd.trigger_targets(ConditionChanged::gained(condition.clone(), None), e);
+            }
+        }
+        ConsumableEffect::RemoveCondition(condition) => {
+            let Some(core) = core else {
+                return;
+            };
+            if core.conditions.contains(condition) {
+                core.conditions.retain(|c| c != condition);
+                cmd.trigger_targets(ConditionChanged::lost(condition.clone()), e);
+            }
+        }
+        ConsumableEffect::Buff(buff) => cmd.trigger_targets(GrantBuff(buff.clone()), e),
+    }
+}
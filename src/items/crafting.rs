@@ -0,0 +1,360 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadedFolder},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use crate::asset_errors::toast_load_failures;
+use crate::{
+    asset_errors::{DataAssetError, Validate, ValidationError},
+    rpg_data::{
+        core::{CoreData, Skills, TrainingLevel},
+        dice::{DegreeOfSuccess, DiceRng},
+        turns::{TurnPhase, TurnTick},
+    },
+    toast::ToastEvent,
+};
+
+use super::{
+    inventory::{EquippedBulk, Inventory, InventoryFull},
+    pickup::SpawnWorldItem,
+    registry::{validate_item_references, ItemId, ItemReferences, ItemRegistry},
+    Item, ItemType,
+};
+
+const RECIPE_FOLDER: &str = "recipe";
+/// Downtime is compressed, a day of crafting passes in this many turns
+pub const TURNS_PER_CRAFTING_DAY: u32 = 10;
+
+pub struct CraftingPlugin;
+
+impl Plugin for CraftingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Recipe>();
+        app.init_asset_loader::<RecipeAssetLoader>();
+        app.init_resource::<RecipeFolder>();
+        app.add_systems(Startup, load_recipe_folder);
+        app.add_systems(
+            Update,
+            (
+                tick_crafting.run_if(on_event::<TurnTick>),
+                validate_item_references::<Recipe>,
+            ),
+        );
+        #[cfg(debug_assertions)]
+        app.add_systems(Update, toast_load_failures::<Recipe>);
+        app.add_observer(craft_item);
+    }
+}
+
+/// A formula for crafting items. Characters can only use the ones listed in their `CoreData.formulas`
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    pub id: String,
+    pub inputs: Vec<RecipeItem>,
+    pub outputs: Vec<RecipeItem>,
+    pub time: CraftingTime,
+    pub dc: i32,
+    /// Crafting proficiency needed to attempt this recipe
+    #[serde(default = "default_training")]
+    pub training: TrainingLevel,
+}
+
+fn default_training() -> TrainingLevel {
+    TrainingLevel::Trained
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeItem {
+    pub item: ItemId,
+    #[serde(default = "default_count")]
+    pub count: u32,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum CraftingTime {
+    Turns(u32),
+    Days(u32),
+}
+
+impl CraftingTime {
+    pub fn turns(&self) -> u32 {
+        match *self {
+            CraftingTime::Turns(turns) => turns,
+            CraftingTime::Days(days) => days * TURNS_PER_CRAFTING_DAY,
+        }
+    }
+}
+
+impl Validate for Recipe {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.id.trim().is_empty() {
+            return Err(ValidationError::new("id", "id must not be empty"));
+        }
+        if self.outputs.is_empty() {
+            return Err(ValidationError::new("outputs", "outputs must not be empty"));
+        }
+        if self
+            .inputs
+            .iter()
+            .chain(self.outputs.iter())
+            .any(|i| i.count == 0)
+        {
+            return Err(ValidationError::new("count", "count must be > 0"));
+        }
+        if self.time.turns() == 0 {
+            return Err(ValidationError::new("time", "time must be > 0"));
+        }
+        Ok(())
+    }
+}
+
+impl ItemReferences for Recipe {
+    fn item_references(&self) -> Vec<((&'static str, usize), &ItemId)> {
+        let inputs = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (("inputs", i), &r.item));
+        let outputs = self
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (("outputs", i), &r.item));
+        inputs.chain(outputs).collect()
+    }
+}
+
+#[derive(Default)]
+pub struct RecipeAssetLoader;
+
+impl AssetLoader for RecipeAssetLoader {
+    type Asset = Recipe;
+    type Settings = ();
+    type Error = DataAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let path = load_context.path();
+        let mut buffer = String::new();
+        reader
+            .read_to_string(&mut buffer)
+            .await
+            .map_err(|e| DataAssetError::io(path, e))?;
+        let recipe =
+            serde_json::from_str::<Recipe>(&buffer).map_err(|e| DataAssetError::json(path, e))?;
+        recipe
+            .validate()
+            .map_err(|e| DataAssetError::invalid(path, &buffer, e))?;
+        Ok(recipe)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["recipe.json"]
+    }
+}
+
+/// Keeps every recipe under `assets/recipe/` loaded
+#[derive(Resource, Default)]
+struct RecipeFolder(Handle<LoadedFolder>);
+
+fn load_recipe_folder(assets: Res<AssetServer>, mut folder: ResMut<RecipeFolder>) {
+    folder.0 = assets.load_folder(RECIPE_FOLDER);
+}
+
+/// Trigger on an entity with `CoreData` and an `Inventory` to start crafting a recipe, by id.
+///
+/// The materials are taken right away and the Crafting check is rolled up front, the outcome arrives once the crafting
+/// time has passed
+#[derive(Debug, Event, Clone)]
+pub struct CraftItem(pub String);
+
+/// Present while an entity is crafting something
+#[derive(Debug, Component)]
+pub struct Crafting {
+    pub recipe: Recipe,
+    pub degree: DegreeOfSuccess,
+    pub turns_left: u32,
+}
+
+fn craft_item(
+    trigger: Trigger<CraftItem>,
+    mut q: Query<(&CoreData, &mut Inventory, Has<Crafting>)>,
+    recipes: Res<Assets<Recipe>>,
+    registry: Res<ItemRegistry>,
+    mut rng: ResMut<DiceRng>,
+    mut cmd: Commands,
+) {
+    let e = trigger.entity();
+    let Ok((core, mut inventory, crafting)) = q.get_mut(e) else {
+        return;
+    };
+    if crafting {
+        cmd.trigger(ToastEvent("Already crafting something".to_owned()));
+        return;
+    }
+    let Some((_, recipe)) = recipes.iter().find(|(_, r)| r.id == trigger.0) else {
+        warn!("Unknown recipe '{}'", trigger.0);
+        return;
+    };
+    if !core.formulas.contains(&recipe.id) {
+        cmd.trigger(ToastEvent(format!(
+            "{} doesn't know the formula for {}",
+            core.name, recipe.id
+        )));
+        return;
+    }
+    if core.training(Skills::Crafting).get_modifier() < recipe.training.get_modifier() {
+        cmd.trigger(ToastEvent(format!(
+            "{} isn't trained enough in Crafting for {}",
+            core.name, recipe.id
+        )));
+        return;
+    }
+    let referenced_by = format!("Recipe '{}'", recipe.id);
+    if !recipe
+        .outputs
+        .iter()
+        .all(|output| registry.check_reference(&output.item, &referenced_by, &mut cmd))
+    {
+        return;
+    }
+    let mut materials = Vec::new();
+    for input in recipe.inputs.iter() {
+        if !registry.check_reference(&input.item, &referenced_by, &mut cmd) {
+            return;
+        }
+        let Some(handle) = registry.get(&input.item) else {
+            return;
+        };
+        if inventory.count(handle) < input.count {
+            cmd.trigger(ToastEvent(format!(
+                "Not enough {} to craft {}",
+                input.item, recipe.id
+            )));
+            return;
+        }
+        materials.push((handle, input.count));
+    }
+    for (handle, count) in materials {
+        for _ in 0..count {
+            inventory.take_one(handle);
+        }
+    }
+
+    let natural = rng.d20();
+    let total = natural as i32 + core.skill_modifier(Skills::Crafting) as i32;
+    let degree = DegreeOfSuccess::from_check(natural, total, recipe.dc);
+    let turns = match degree {
+        DegreeOfSuccess::CriticalSuccess => recipe.time.turns().div_ceil(2),
+        _ => recipe.time.turns(),
+    };
+    cmd.entity(e).insert(Crafting {
+        recipe: recipe.clone(),
+        degree,
+        turns_left: turns,
+    });
+    cmd.trigger(ToastEvent(format!("Started crafting {}", recipe.id)));
+}
+
+/// A success or better yields the outputs, with a critical success taking half the time. On a failure the materials
+/// are returned, on a critical failure a tenth of them (rounded up) is ruined
+fn tick_crafting(
+    mut events: EventReader<TurnTick>,
+    mut q: Query<(
+        Entity,
+        &mut Crafting,
+        &mut Inventory,
+        Option<&CoreData>,
+        Option<&EquippedBulk>,
+        Option<&GlobalTransform>,
+    )>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let turns = events
+        .read()
+        .filter(|tick| tick.phase == TurnPhase::Start)
+        .count() as u32;
+    if turns == 0 {
+        return;
+    }
+    for (e, mut crafting, mut inventory, core, equipped, transform) in q.iter_mut() {
+        crafting.turns_left = crafting.turns_left.saturating_sub(turns);
+        if crafting.turns_left > 0 {
+            continue;
+        }
+        cmd.entity(e).remove::<Crafting>();
+        let recipe = &crafting.recipe;
+        let (results, message) = match crafting.degree {
+            DegreeOfSuccess::CriticalSuccess | DegreeOfSuccess::Success => {
+                let names: Vec<String> = recipe
+                    .outputs
+                    .iter()
+                    .map(|output| {
+                        let name = registry
+                            .get(&output.item)
+                            .and_then(|h| items.get(h.id()))
+                            .map_or(output.item.clone(), |item| item.get_name().clone());
+                        format!("{}x {name}", output.count)
+                    })
+                    .collect();
+                (
+                    recipe.outputs.clone(),
+                    format!("Crafted {}", names.join(", ")),
+                )
+            }
+            DegreeOfSuccess::Failure => (
+                recipe.inputs.clone(),
+                format!("Failed to craft {}, the materials are salvaged", recipe.id),
+            ),
+            DegreeOfSuccess::CriticalFailure => (
+                recipe
+                    .inputs
+                    .iter()
+                    .map(|input| RecipeItem {
+                        item: input.item.clone(),
+                        count: input.count - input.count.div_ceil(10),
+                    })
+                    .filter(|input| input.count > 0)
+                    .collect(),
+                format!("Failed to craft {}, some materials are ruined", recipe.id),
+            ),
+        };
+        for result in results {
+            let Some(handle) = registry.get(&result.item) else {
+                continue;
+            };
+            let fits = items
+                .get(handle.id())
+                .is_none_or(|item| inventory.fits(core, equipped, item, result.count, &items));
+            if fits {
+                inventory.add(handle.clone(), result.count);
+                continue;
+            }
+            // whatever is too heavy to carry is left on the ground
+            if let Some(transform) = transform {
+                cmd.trigger(SpawnWorldItem::new(
+                    handle.clone(),
+                    result.count,
+                    transform.translation(),
+                ));
+            }
+            let name = items
+                .get(handle.id())
+                .map_or(result.item.clone(), |item| item.get_name().clone());
+            cmd.trigger_targets(InventoryFull { item: name }, e);
+        }
+        cmd.trigger(ToastEvent(message));
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt::Display;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const COPPER_PER_SILVER: u64 = 10;
+pub const COPPER_PER_GOLD: u64 = 100;
+pub const COPPER_PER_PLATINUM: u64 = 1000;
+
+/// An amount of PF2e coins, used both for prices and for what a character carries
+#[derive(Debug, Reflect, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Coins {
+    #[serde(default)]
+    pub pp: u32,
+    #[serde(default)]
+    pub gp: u32,
+    #[serde(default)]
+    pub sp: u32,
+    #[serde(default)]
+    pub cp: u32,
+}
+
+impl Coins {
+    pub fn gold(gp: u32) -> Self {
+        Self { gp, ..default() }
+    }
+
+    /// Converts copper into the fewest platinum, gold, silver and copper pieces
+    pub fn from_copper(copper: u64) -> Self {
+        Self {
+            pp: (copper / COPPER_PER_PLATINUM) as u32,
+            gp: (copper % COPPER_PER_PLATINUM / COPPER_PER_GOLD) as u32,
+            sp: (copper % COPPER_PER_GOLD / COPPER_PER_SILVER) as u32,
+            cp: (copper % COPPER_PER_SILVER) as u32,
+        }
+    }
+
+    pub fn in_copper(&self) -> u64 {
+        self.pp as u64 * COPPER_PER_PLATINUM
+            + self.gp as u64 * COPPER_PER_GOLD
+            + self.sp as u64 * COPPER_PER_SILVER
+            + self.cp as u64
+    }
+
+    /// Scales the value, rounding down to the nearest copper
+    pub fn scaled(&self, multiplier: f32) -> Self {
+        Self::from_copper((self.in_copper() as f64 * multiplier.max(0.0) as f64) as u64)
+    }
+
+    pub fn times(&self, count: u32) -> Self {
+        Self::from_copper(self.in_copper() * count as u64)
+    }
+}
+
+impl Display for Coins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = [
+            (self.pp, "pp"),
+            (self.gp, "gp"),
+            (self.sp, "sp"),
+            (self.cp, "cp"),
+        ]
+        .into_iter()
+        .filter(|(amount, _)| *amount > 0)
+        .map(|(amount, unit)| format!("{amount} {unit}"))
+        .collect();
+        if parts.is_empty() {
+            return f.write_str("0 cp");
+        }
+        f.write_str(&parts.join(" "))
+    }
+}
+
+/// Coins carried by a character or merchant
+#[derive(Debug, Component, Reflect, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Wallet(pub Coins);
+
+impl Wallet {
+    pub fn can_afford(&self, price: Coins) -> bool {
+        self.0.in_copper() >= price.in_copper()
+    }
+
+    /// Pays `price`, breaking larger coins for change as needed. Returns false and leaves the wallet untouched if there
+    /// isn't enough money
+    pub fn pay(&mut self, price: Coins) -> bool {
+        if !self.can_afford(price) {
+            return false;
+        }
+        self.0 = Coins::from_copper(self.0.in_copper() - price.in_copper());
+        true
+    }
+
+    pub fn receive(&mut self, amount: Coins) {
+        self.0 = Coins::from_copper(self.0.in_copper() + amount.in_copper());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paying_breaks_larger_coins_for_change() {
+        let mut wallet = Wallet(Coins::gold(1));
+        assert!(wallet.pay(Coins {
+            sp: 2,
+            cp: 5,
+            ..default()
+        }));
+        assert_eq!(
+            wallet.0,
+            Coins {
+                sp: 7,
+                cp: 5,
+                ..default()
+            }
+        );
+    }
+
+    #[test]
+    fn change_is_normalised_to_the_fewest_coins() {
+        let mut wallet = Wallet(Coins {
+            sp: 25,
+            cp: 30,
+            ..default()
+        });
+        assert!(wallet.pay(Coins { cp: 5, ..default() }));
+        assert_eq!(
+            wallet.0,
+            Coins {
+                gp: 2,
+                sp: 7,
+                cp: 5,
+                ..default()
+            }
+        );
+        wallet.receive(Coins {
+            gp: 9,
+            sp: 3,
+            ..default()
+        });
+        assert_eq!(
+            wallet.0,
+            Coins {
+                pp: 1,
+                gp: 2,
+                cp: 5,
+                ..default()
+            }
+        );
+    }
+
+    #[test]
+    fn failed_payment_leaves_the_wallet_alone() {
+        let coins = Coins {
+            sp: 9,
+            cp: 9,
+            ..default()
+        };
+        let mut wallet = Wallet(coins);
+        assert!(!wallet.pay(Coins::gold(1)));
+        assert_eq!(wallet.0, coins);
+    }
+}
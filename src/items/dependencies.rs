@@ -0,0 +1,64 @@
+use bevy::{asset::LoadContext, prelude::*, utils::hashbrown::HashMap};
+use bevy_kira_audio::AudioSource;
+use serde::{Deserialize, Serialize};
+
+use super::{Item, ItemType};
+
+/// Paths of other assets an item uses, the loader loads them as dependencies of the item and fills in `handles`
+#[derive(Debug, Reflect, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ItemAssets {
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Sound effects by what they play for, such as "equip" or "hit"
+    #[serde(default)]
+    pub sounds: HashMap<String, String>,
+    #[serde(skip)]
+    pub handles: ItemHandles,
+}
+
+#[derive(Debug, Reflect, Clone, PartialEq, Default)]
+pub struct ItemHandles {
+    pub icon: Option<Handle<Image>>,
+    /// First scene of the item's `ItemModel`
+    pub model: Option<Handle<Scene>>,
+    pub sounds: HashMap<String, Handle<AudioSource>>,
+    /// The ammunition item of ranged weapons
+    pub ammunition: Option<Handle<ItemType>>,
+}
+
+impl ItemType {
+    pub fn assets(&self) -> &ItemAssets {
+        &self.common().assets
+    }
+
+    /// Starts loading every asset the item references through `load_context`, so that the item only counts as loaded
+    /// with dependencies once they are all ready
+    pub fn load_dependencies(&mut self, load_context: &mut LoadContext) {
+        let model = self.get_model().map(|model| {
+            load_context.load(GltfAssetLabel::Scene(0).from_asset(model.path.clone()))
+        });
+        let ammunition = match self {
+            ItemType::Weapon(weapon_item) => weapon_item
+                .ammunition
+                .as_ref()
+                .map(|path| load_context.load(path.clone())),
+            _ => None,
+        };
+        let assets = &mut self.common_mut().assets;
+        let icon = assets
+            .icon
+            .as_ref()
+            .map(|path| load_context.load(path.clone()));
+        let sounds = assets
+            .sounds
+            .iter()
+            .map(|(name, path)| (name.clone(), load_context.load(path.clone())))
+            .collect();
+        assets.handles = ItemHandles {
+            icon,
+            model,
+            sounds,
+            ammunition,
+        };
+    }
+}
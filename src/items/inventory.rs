@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    rpg_data::{
+        conditions::ConditionChanged,
+        core::{CoreData, Stats},
+    },
+    toast::ToastEvent,
+};
+
+use super::{Item, ItemState, ItemType};
+
+pub const ENCUMBERED_CONDITION: &str = "encumbered";
+/// Speed penalty (in feet) while encumbered
+pub const ENCUMBERED_SPEED_PENALTY: u32 = 10;
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_encumbrance);
+        app.add_observer(toast_inventory_full);
+    }
+}
+
+/// PF2e Bulk of a single item
+#[derive(
+    Debug, Reflect, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum Bulk {
+    #[default]
+    Negligible,
+    /// 10 light items add up to 1 Bulk
+    Light,
+    Bulk(u32),
+}
+
+impl Bulk {
+    /// Bulk in tenths, which makes light items easy to sum
+    pub fn tenths(&self) -> u32 {
+        match *self {
+            Bulk::Negligible => 0,
+            Bulk::Light => 1,
+            Bulk::Bulk(value) => value * 10,
+        }
+    }
+}
+
+/// Returns the Bulk at which a character becomes encumbered and the most Bulk they can carry at all
+pub fn bulk_limits(core: &CoreData) -> (u32, u32) {
+    let strength = core.modifier(Stats::Strength) as i32;
+    ((5 + strength).max(0) as u32, (10 + strength).max(0) as u32)
+}
+
+/// Copies of an item that share the same `ItemState`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    pub item: Handle<ItemType>,
+    pub state: ItemState,
+    pub count: u32,
+}
+
+#[derive(Debug, Component, Clone, Default)]
+pub struct Inventory {
+    pub stacks: Vec<ItemStack>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: Handle<ItemType>, count: u32) {
+        self.add_with_state(item, ItemState::default(), count);
+    }
+
+    pub fn add_with_state(&mut self, item: Handle<ItemType>, state: ItemState, count: u32) {
+        match self
+            .stacks
+            .iter_mut()
+            .find(|s| s.item == item && s.state == state)
+        {
+            Some(stack) => stack.count += count,
+            None => self.stacks.push(ItemStack { item, state, count }),
+        }
+    }
+
+    /// Removes a single copy of an item and returns its state
+    pub fn take_one(&mut self, item: &Handle<ItemType>) -> Option<ItemState> {
+        let index = self.stacks.iter().position(|s| &s.item == item)?;
+        let stack = &mut self.stacks[index];
+        stack.count -= 1;
+        let state = stack.state.clone();
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        Some(state)
+    }
+
+    /// Removes `count` of an item from a single stack, returns false (and leaves the inventory untouched) if no stack
+    /// has enough
+    pub fn remove(&mut self, item: &Handle<ItemType>, count: u32) -> bool {
+        let Some(index) = self
+            .stacks
+            .iter()
+            .position(|s| &s.item == item && s.count >= count)
+        else {
+            return false;
+        };
+        let stack = &mut self.stacks[index];
+        stack.count -= count;
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        true
+    }
+
+    /// Changes the state of a single copy of an item, splitting it off its stack. Returns false if no copy is in the
+    /// `from` state
+    pub fn set_state(&mut self, item: &Handle<ItemType>, from: &ItemState, to: ItemState) -> bool {
+        let Some(index) = self
+            .stacks
+            .iter()
+            .position(|s| &s.item == item && &s.state == from)
+        else {
+            return false;
+        };
+        let stack = &mut self.stacks[index];
+        stack.count -= 1;
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        self.add_with_state(item.clone(), to, 1);
+        true
+    }
+
+    pub fn count_with_state(&self, item: &Handle<ItemType>, state: &ItemState) -> u32 {
+        self.stacks
+            .iter()
+            .find(|s| &s.item == item && &s.state == state)
+            .map_or(0, |s| s.count)
+    }
+
+    pub fn count(&self, item: &Handle<ItemType>) -> u32 {
+        self.stacks
+            .iter()
+            .filter(|s| &s.item == item)
+            .map(|s| s.count)
+            .sum()
+    }
+
+    fn bulk_tenths(&self, items: &Assets<ItemType>) -> u32 {
+        self.stacks
+            .iter()
+            .filter_map(|s| {
+                items
+                    .get(s.item.id())
+                    .map(|i| i.get_bulk().tenths() * s.count)
+            })
+            .sum()
+    }
+
+    /// Total Bulk carried including the equipped items, fractions of light items are dropped
+    pub fn total_bulk(&self, equipped: Option<&EquippedBulk>, items: &Assets<ItemType>) -> u32 {
+        (self.bulk_tenths(items) + equipped.map_or(0, |e| e.0)) / 10
+    }
+
+    /// Whether `count` more of `item` stay within the maximum of `bulk_limits`. Without `CoreData` there is no limit
+    pub fn fits(
+        &self,
+        core: Option<&CoreData>,
+        equipped: Option<&EquippedBulk>,
+        item: &ItemType,
+        count: u32,
+        items: &Assets<ItemType>,
+    ) -> bool {
+        let Some(core) = core else {
+            return true;
+        };
+        let (_, max) = bulk_limits(core);
+        let carried = self.bulk_tenths(items) + equipped.map_or(0, |e| e.0);
+        (carried + item.get_bulk().tenths() * count) / 10 <= max
+    }
+}
+
+/// Bulk in tenths of the items held and worn outside of the `Inventory`, kept up to date by the equipment
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EquippedBulk(pub u32);
+
+/// Triggered on an entity when an item is left out of its inventory because it would go over the maximum Bulk
+#[derive(Debug, Event, Clone)]
+pub struct InventoryFull {
+    /// Name of the item that didn't fit
+    pub item: String,
+}
+
+/// Marks a character carrying more than `bulk_limits` allows before becoming encumbered
+#[derive(Debug, Component)]
+pub struct Encumbered;
+
+fn update_encumbrance(
+    mut q: Query<(
+        Entity,
+        &Inventory,
+        Option<&EquippedBulk>,
+        &mut CoreData,
+        Has<Encumbered>,
+    )>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    for (e, inventory, equipped, mut core, encumbered) in q.iter_mut() {
+        let (encumbered_at, _) = bulk_limits(&core);
+        let should_be_encumbered = inventory.total_bulk(equipped, &items) > encumbered_at;
+        if should_be_encumbered == encumbered {
+            continue;
+        }
+        if should_be_encumbered {
+            cmd.entity(e).insert(Encumbered);
+            core.conditions.push(ENCUMBERED_CONDITION.to_owned());
+            cmd.trigger_targets(ConditionChanged::gained(ENCUMBERED_CONDITION, None), e);
+        } else {
+            cmd.entity(e).remove::<Encumbered>();
+            core.conditions.retain(|c| c != ENCUMBERED_CONDITION);
+            cmd.trigger_targets(ConditionChanged::lost(ENCUMBERED_CONDITION), e);
+        }
+    }
+}
+
+fn toast_inventory_full(trigger: Trigger<InventoryFull>, mut cmd: Commands) {
+    cmd.trigger(ToastEvent(format!(
+        "{} is too heavy to carry",
+        trigger.item
+    )));
+}
@@ -8,22 +8,10 @@ pub struct LoadTestPlugin;
 
 impl Plugin for LoadTestPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<ItemRegistry>();
-        app.add_systems(Startup, dispatch_load);
         app.add_systems(Update, events_item_added);
     }
 }
 
-#[derive(Resource, Default)]
-struct ItemRegistry {
-    items: Vec<Handle<ItemType>>,
-}
-
-fn dispatch_load(assets: Res<AssetServer>, mut registry: ResMut<ItemRegistry>) {
-    registry.items.push(assets.load("item/test_basic.json"));
-    registry.items.push(assets.load("item/test_weapon.json"));
-}
-
 fn events_item_added(
     mut events: EventReader<AssetEvent<ItemType>>,
     items: Res<Assets<ItemType>>,
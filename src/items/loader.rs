@@ -0,0 +1,307 @@
+use std::path::Path;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    log::warn,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::asset_errors::{DataAssetError, DataAssetErrorKind, Validate};
+
+use super::ItemType;
+
+/// Field inside the item variant naming another item asset to inherit every field from, the item's own fields
+/// override the inherited ones
+const BASE_FIELD: &str = "base";
+
+#[derive(Default)]
+pub struct ItemAssetLoader;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ItemLoaderSettings {
+    /// Reject items with fields that aren't part of their item type instead of silently ignoring them. RON items can't
+    /// be compared field by field as plain data, so they are loaded as if this was off
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ItemFormat {
+    Json,
+    Ron,
+    Toml,
+}
+
+impl ItemFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(ItemFormat::Json),
+            "ron" => Some(ItemFormat::Ron),
+            "toml" => Some(ItemFormat::Toml),
+            _ => None,
+        }
+    }
+
+    fn parse<T: DeserializeOwned>(self, path: &Path, text: &str) -> Result<T, DataAssetError> {
+        let syntax =
+            |message: String| DataAssetError::new(path, DataAssetErrorKind::Syntax(message));
+        match self {
+            ItemFormat::Json => {
+                serde_json::from_str(text).map_err(|e| DataAssetError::json(path, e))
+            }
+            ItemFormat::Ron => ron::from_str(text)
+                .map_err(|e| syntax(e.code.to_string()).at(e.position.line, e.position.col)),
+            ItemFormat::Toml => toml::from_str(text).map_err(|e| {
+                let error = syntax(e.message().to_owned());
+                match e.span() {
+                    Some(span) => error.at_offset(text, span.start),
+                    None => error,
+                }
+            }),
+        }
+    }
+
+    fn write<T: Serialize>(self, path: &Path, value: &T) -> Result<String, DataAssetError> {
+        match self {
+            ItemFormat::Json => serde_json::to_string(value).map_err(|e| e.to_string()),
+            ItemFormat::Ron => ron::to_string(value).map_err(|e| e.to_string()),
+            ItemFormat::Toml => toml::to_string(value).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| DataAssetError::new(path, DataAssetErrorKind::Syntax(e)))
+    }
+}
+
+impl AssetLoader for ItemAssetLoader {
+    type Asset = ItemType;
+    type Settings = ItemLoaderSettings;
+    type Error = DataAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let path = load_context.path().to_owned();
+        let format = ItemFormat::from_path(&path)
+            .ok_or_else(|| DataAssetError::new(&path, DataAssetErrorKind::UnsupportedFormat))?;
+        let mut buffer = String::new();
+        reader
+            .read_to_string(&mut buffer)
+            .await
+            .map_err(|e| DataAssetError::io(&path, e))?;
+        // formats that can't be read as plain data (such as RON enum variants) can't name a base item
+        let mut source: Option<Value> = format.parse(&path, &buffer).ok();
+        let base = source.as_mut().and_then(take_base);
+        let item: ItemType = match (&base, &source) {
+            (Some(base), Some(overrides)) => {
+                inherit(load_context, &path, base, overrides.clone()).await?
+            }
+            _ => format.parse(&path, &buffer)?,
+        };
+        let mut item = finish(format, &path, &buffer, source, item, settings.strict)?;
+        item.load_dependencies(load_context);
+        Ok(item)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["item.json", "item.ron", "item.toml"]
+    }
+}
+
+/// Checks for unknown fields in strict mode and validates the item, whether it has a base or not
+fn finish(
+    format: ItemFormat,
+    path: &Path,
+    buffer: &str,
+    source: Option<Value>,
+    item: ItemType,
+    strict: bool,
+) -> Result<ItemType, DataAssetError> {
+    if strict {
+        check_unknown_fields(format, path, buffer, source, &item)?;
+    }
+    item.validate()
+        .map_err(|e| DataAssetError::invalid(path, buffer, e))?;
+    Ok(item)
+}
+
+fn check_unknown_fields(
+    format: ItemFormat,
+    path: &Path,
+    buffer: &str,
+    source: Option<Value>,
+    item: &ItemType,
+) -> Result<(), DataAssetError> {
+    if let ItemFormat::Ron = format {
+        // bare identifier keys and enum syntax don't map onto plain JSON values
+        warn!(
+            "Strict mode doesn't support RON items, '{}' isn't checked for unknown fields",
+            path.display()
+        );
+        return Ok(());
+    }
+    let source = match source {
+        Some(source) => source,
+        None => format.parse(path, buffer)?,
+    };
+    // Parse the item written back out the same way as the source, anything only present in the source was ignored
+    // while deserializing
+    let known: Value = format.parse(path, &format.write(path, item)?)?;
+    let mut unknown = Vec::new();
+    find_unknown_fields(&source, &known, "", &mut unknown);
+    if !unknown.is_empty() {
+        return Err(DataAssetError::new(
+            path,
+            DataAssetErrorKind::UnknownFields(unknown),
+        ));
+    }
+    Ok(())
+}
+
+/// Loads the base item and applies the overrides on top of it
+async fn inherit(
+    load_context: &mut LoadContext<'_>,
+    path: &Path,
+    base: &str,
+    overrides: Value,
+) -> Result<ItemType, DataAssetError> {
+    let loaded = load_context
+        .loader()
+        .immediate()
+        .load::<ItemType>(base)
+        .await
+        .map_err(|e| {
+            DataAssetError::new(
+                path,
+                DataAssetErrorKind::Dependency {
+                    path: base.to_owned(),
+                    message: e.to_string(),
+                },
+            )
+        })?;
+    let syntax =
+        |e: serde_json::Error| DataAssetError::new(path, DataAssetErrorKind::Syntax(e.to_string()));
+    let mut merged = serde_json::to_value(loaded.get()).map_err(syntax)?;
+    if !same_variant(&merged, &overrides) {
+        return Err(DataAssetError::new(
+            path,
+            DataAssetErrorKind::Invalid(format!("base item '{base}' is a different kind of item")),
+        ));
+    }
+    merge(&mut merged, overrides);
+    serde_json::from_value(merged).map_err(syntax)
+}
+
+/// Removes the `base` field from inside the item variant, returning the path of the base item if there was one
+fn take_base(source: &mut Value) -> Option<String> {
+    let fields = source
+        .as_object_mut()?
+        .values_mut()
+        .next()?
+        .as_object_mut()?;
+    match fields.remove(BASE_FIELD)? {
+        Value::String(base) => Some(base),
+        _ => None,
+    }
+}
+
+fn same_variant(a: &Value, b: &Value) -> bool {
+    let variant = |v: &Value| v.as_object().and_then(|o| o.keys().next().cloned());
+    variant(a).is_some() && variant(a) == variant(b)
+}
+
+/// Overrides the fields of `base` with the ones in `overrides`, nested objects are merged field by field
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+fn find_unknown_fields(source: &Value, known: &Value, path: &str, unknown: &mut Vec<String>) {
+    match (source, known) {
+        (Value::Object(source), Value::Object(known)) => {
+            for (key, value) in source {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match known.get(key) {
+                    Some(known) => find_unknown_fields(value, known, &field, unknown),
+                    None => unknown.push(field),
+                }
+            }
+        }
+        (Value::Array(source), Value::Array(known)) => {
+            for (i, (value, known)) in source.iter().zip(known).enumerate() {
+                find_unknown_fields(value, known, &format!("{path}[{i}]"), unknown);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Same steps as the loader, with base items read straight from the assets folder
+    fn load_strict(path: &Path) -> Result<ItemType, DataAssetError> {
+        let buffer = std::fs::read_to_string(path).map_err(|e| DataAssetError::io(path, e))?;
+        let format = ItemFormat::from_path(path).expect("unsupported item format");
+        let mut source: Option<Value> = format.parse(path, &buffer).ok();
+        let base = source.as_mut().and_then(take_base);
+        let item = match (base, &source) {
+            (Some(base), Some(overrides)) => {
+                let loaded = load_strict(&Path::new("assets").join(base))?;
+                let mut merged = serde_json::to_value(loaded).unwrap();
+                merge(&mut merged, overrides.clone());
+                serde_json::from_value(merged).unwrap()
+            }
+            _ => format.parse(path, &buffer)?,
+        };
+        finish(format, path, &buffer, source, item, true)
+    }
+
+    #[test]
+    fn samples_load_in_strict_mode() {
+        for entry in std::fs::read_dir("assets/item").unwrap() {
+            let path = entry.unwrap().path();
+            if !path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("test_")
+            {
+                continue;
+            }
+            if let Err(e) = load_strict(&path) {
+                panic!("{e}");
+            }
+        }
+    }
+
+    #[test]
+    fn strict_mode_reports_unknown_fields() {
+        let path = Path::new("test_extra.item.json");
+        let buffer = r#"{"Basic": {"id": "test_extra", "name": "test extra", "colour": "red"}}"#;
+        let source = ItemFormat::Json.parse(path, buffer).ok();
+        let item = ItemFormat::Json.parse(path, buffer).unwrap();
+        let error = finish(ItemFormat::Json, path, buffer, source, item, true).unwrap_err();
+        let DataAssetErrorKind::UnknownFields(fields) = error.kind else {
+            panic!("{error}");
+        };
+        assert_eq!(fields, vec!["Basic.colour".to_owned()]);
+    }
+}
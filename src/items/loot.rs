@@ -0,0 +1,409 @@
+use avian3d::prelude::{CollidingEntities, Sensor};
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::hashbrown::HashMap,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use crate::asset_errors::toast_load_failures;
+use crate::{
+    asset_errors::{DataAssetError, Validate, ValidationError},
+    health::death::{Died, LootDropped},
+    rpg_data::{core::CoreData, dice::DiceRng},
+    toast::ToastEvent,
+};
+
+use super::{
+    inventory::ItemStack,
+    registry::{validate_item_references, ItemId, ItemReferences, ItemRegistry},
+    ItemState,
+};
+
+/// Nested tables deeper than this are skipped, which also stops tables that include each other
+const MAX_TABLE_DEPTH: u32 = 8;
+/// How far above a chest its loot is dropped
+const CHEST_DROP_HEIGHT: f32 = 0.5;
+
+pub struct LootPlugin;
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LootTable>();
+        app.init_asset_loader::<LootTableLoader>();
+        #[cfg(debug_assertions)]
+        app.add_systems(
+            Update,
+            (toast_load_failures::<LootTable>, roll_test_table_on_input),
+        );
+        app.add_systems(Update, validate_item_references::<LootTable>);
+        app.add_observer(roll_loot_on_death);
+        app.add_observer(open_chest);
+        app.add_observer(print_loot_distribution);
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct LootTable {
+    /// How many entries are picked every time the table is rolled
+    #[serde(default = "default_rolls")]
+    pub rolls: u32,
+    pub entries: Vec<LootEntry>,
+    /// The tables referenced by `LootDrop::Table`, keyed by their path. Filled in by the loader
+    #[serde(skip)]
+    pub tables: HashMap<String, Handle<LootTable>>,
+}
+
+fn default_rolls() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LootEntry {
+    /// Chance of this entry relative to the other entries available at the rolled level
+    pub weight: u32,
+    pub drop: LootDrop,
+    #[serde(default)]
+    pub min_level: Option<i16>,
+    #[serde(default)]
+    pub max_level: Option<i16>,
+}
+
+impl LootEntry {
+    pub fn allows(&self, level: i16) -> bool {
+        self.min_level.is_none_or(|min| level >= min)
+            && self.max_level.is_none_or(|max| level <= max)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LootDrop {
+    Nothing,
+    Item {
+        id: ItemId,
+        #[serde(default)]
+        count: Quantity,
+    },
+    /// Rolls another loot table, by asset path
+    Table(String),
+}
+
+/// Inclusive range of how many copies drop
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Quantity {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Default for Quantity {
+    fn default() -> Self {
+        Self { min: 1, max: 1 }
+    }
+}
+
+impl LootTable {
+    /// Rolls the table for a source of the given level, returns every dropped item id with its count
+    pub fn roll(
+        &self,
+        level: i16,
+        tables: &Assets<LootTable>,
+        rng: &mut DiceRng,
+    ) -> Vec<(ItemId, u32)> {
+        let mut loot = Vec::new();
+        self.roll_into(level, tables, rng, 0, &mut loot);
+        loot
+    }
+
+    fn roll_into(
+        &self,
+        level: i16,
+        tables: &Assets<LootTable>,
+        rng: &mut DiceRng,
+        depth: u32,
+        loot: &mut Vec<(ItemId, u32)>,
+    ) {
+        if depth > MAX_TABLE_DEPTH {
+            warn!("Loot tables nested deeper than {MAX_TABLE_DEPTH}, skipping the rest");
+            return;
+        }
+        let available: Vec<&LootEntry> = self.entries.iter().filter(|e| e.allows(level)).collect();
+        let total: u32 = available.iter().map(|e| e.weight).sum();
+        if total == 0 {
+            return;
+        }
+        for _ in 0..self.rolls {
+            let mut pick = rng.0.gen_range(0..total);
+            let Some(entry) = available.iter().find(|e| {
+                if pick < e.weight {
+                    return true;
+                }
+                pick -= e.weight;
+                false
+            }) else {
+                continue;
+            };
+            match &entry.drop {
+                LootDrop::Nothing => (),
+                LootDrop::Item { id, count } => {
+                    let count = rng.0.gen_range(count.min..=count.max);
+                    if count == 0 {
+                        continue;
+                    }
+                    match loot.iter_mut().find(|(existing, _)| existing == id) {
+                        Some((_, total)) => *total += count,
+                        None => loot.push((id.clone(), count)),
+                    }
+                }
+                LootDrop::Table(path) => {
+                    let Some(table) = self.tables.get(path).and_then(|h| tables.get(h.id())) else {
+                        warn!("Nested loot table '{path}' isn't loaded");
+                        continue;
+                    };
+                    table.roll_into(level, tables, rng, depth + 1, loot);
+                }
+            }
+        }
+    }
+}
+
+impl Validate for LootTable {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.rolls == 0 {
+            return Err(ValidationError::new("rolls", "rolls must be > 0"));
+        }
+        if self.entries.is_empty() {
+            return Err(ValidationError::new(
+                "entries",
+                "a loot table needs entries",
+            ));
+        }
+        for (i, entry) in self.entries.iter().enumerate() {
+            if entry.weight == 0 {
+                return Err(
+                    ValidationError::new("weight", "weight must be > 0").in_element("entries", i)
+                );
+            }
+            if let (Some(min), Some(max)) = (entry.min_level, entry.max_level) {
+                if min > max {
+                    return Err(ValidationError::new(
+                        "min_level",
+                        "min_level must be <= max_level",
+                    )
+                    .in_element("entries", i));
+                }
+            }
+            if let LootDrop::Item { count, .. } = &entry.drop {
+                if count.min > count.max {
+                    return Err(ValidationError::new("min", "count min must be <= max")
+                        .in_element("entries", i));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ItemReferences for LootTable {
+    fn item_references(&self) -> Vec<((&'static str, usize), &ItemId)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| match &entry.drop {
+                LootDrop::Item { id, .. } => Some((("entries", i), id)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct LootTableLoader;
+
+impl AssetLoader for LootTableLoader {
+    type Asset = LootTable;
+    type Settings = ();
+    type Error = DataAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let path = load_context.path().to_owned();
+        let mut buffer = String::new();
+        reader
+            .read_to_string(&mut buffer)
+            .await
+            .map_err(|e| DataAssetError::io(&path, e))?;
+        let mut table = serde_json::from_str::<LootTable>(&buffer)
+            .map_err(|e| DataAssetError::json(&path, e))?;
+        table
+            .validate()
+            .map_err(|e| DataAssetError::invalid(&path, &buffer, e))?;
+        for entry in table.entries.iter() {
+            if let LootDrop::Table(nested) = &entry.drop {
+                let handle = load_context.load(nested.clone());
+                table.tables.insert(nested.clone(), handle);
+            }
+        }
+        Ok(table)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["loot.json"]
+    }
+}
+
+/// Rolls this table when the entity dies, at the level of its `CoreData` if it has one
+#[derive(Debug, Component, Clone)]
+pub struct LootTableOnDeath(pub Handle<LootTable>);
+
+/// A container that rolls its table once when opened. Opening removes this component
+#[derive(Debug, Component, Clone)]
+#[require(Sensor, CollidingEntities)]
+pub struct LootChest {
+    pub table: Handle<LootTable>,
+    pub level: i16,
+}
+
+/// Trigger on an entity with `LootChest` to open it
+#[derive(Debug, Event, Clone, Copy)]
+pub struct OpenChest;
+
+/// Debug command, rolls a table many times and logs how often each item dropped
+#[derive(Debug, Event, Clone)]
+pub struct RollLootTable {
+    pub table: Handle<LootTable>,
+    pub times: u32,
+    pub level: i16,
+}
+
+/// Turns rolled item ids into stacks, unknown ids are reported and skipped
+fn resolve_loot(
+    loot: Vec<(ItemId, u32)>,
+    registry: &ItemRegistry,
+    source: &str,
+    cmd: &mut Commands,
+) -> Vec<ItemStack> {
+    loot.into_iter()
+        .filter(|(id, _)| registry.check_reference(id, source, cmd))
+        .filter_map(|(id, count)| {
+            Some(ItemStack {
+                item: registry.get(&id)?.clone(),
+                state: ItemState::default(),
+                count,
+            })
+        })
+        .collect()
+}
+
+fn roll_loot_on_death(
+    trigger: Trigger<Died>,
+    q: Query<(&LootTableOnDeath, &GlobalTransform, Option<&CoreData>)>,
+    tables: Res<Assets<LootTable>>,
+    registry: Res<ItemRegistry>,
+    mut rng: ResMut<DiceRng>,
+    mut cmd: Commands,
+) {
+    let Ok((loot, transform, core)) = q.get(trigger.entity()) else {
+        return;
+    };
+    let Some(table) = tables.get(loot.0.id()) else {
+        return;
+    };
+    let level = core.map_or(0, |c| c.level);
+    let rolled = table.roll(level, &tables, &mut rng);
+    let items = resolve_loot(rolled, &registry, "Loot table", &mut cmd);
+    if items.is_empty() {
+        return;
+    }
+    cmd.trigger(LootDropped {
+        position: transform.translation(),
+        items,
+    });
+}
+
+fn open_chest(
+    trigger: Trigger<OpenChest>,
+    q: Query<(&LootChest, &GlobalTransform)>,
+    tables: Res<Assets<LootTable>>,
+    registry: Res<ItemRegistry>,
+    mut rng: ResMut<DiceRng>,
+    mut cmd: Commands,
+) {
+    let e = trigger.entity();
+    let Ok((chest, transform)) = q.get(e) else {
+        return;
+    };
+    let Some(table) = tables.get(chest.table.id()) else {
+        return;
+    };
+    cmd.entity(e).remove::<LootChest>();
+    let rolled = table.roll(chest.level, &tables, &mut rng);
+    let items = resolve_loot(rolled, &registry, "Loot table", &mut cmd);
+    if items.is_empty() {
+        cmd.trigger(ToastEvent("The chest is empty".to_owned()));
+        return;
+    }
+    cmd.trigger(LootDropped {
+        position: transform.translation() + Vec3::Y * CHEST_DROP_HEIGHT,
+        items,
+    });
+}
+
+fn print_loot_distribution(
+    trigger: Trigger<RollLootTable>,
+    tables: Res<Assets<LootTable>>,
+    assets: Res<AssetServer>,
+    mut rng: ResMut<DiceRng>,
+) {
+    let event = trigger.event();
+    let name = assets.get_path(event.table.id()).unwrap_or_default();
+    let Some(table) = tables.get(event.table.id()) else {
+        warn!("Loot table '{name}' isn't loaded");
+        return;
+    };
+    // item id -> (rolls it dropped in, total count)
+    let mut drops: HashMap<ItemId, (u32, u32)> = HashMap::new();
+    for _ in 0..event.times {
+        for (id, count) in table.roll(event.level, &tables, &mut rng) {
+            let entry = drops.entry(id).or_default();
+            entry.0 += 1;
+            entry.1 += count;
+        }
+    }
+    let mut drops: Vec<_> = drops.into_iter().collect();
+    drops.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.0.cmp(&b.0)));
+    info!(
+        "Rolled '{name}' {} times at level {}:",
+        event.times, event.level
+    );
+    for (id, (rolls, total)) in drops {
+        info!(
+            "  {id}: {:.1}% of rolls, {total} total, {:.2} per roll",
+            rolls as f32 * 100.0 / event.times as f32,
+            total as f32 / event.times as f32
+        );
+    }
+}
+
+#[cfg(debug_assertions)]
+fn roll_test_table_on_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    assets: Res<AssetServer>,
+    mut table: Local<Option<Handle<LootTable>>>,
+    mut cmd: Commands,
+) {
+    // keep the table loaded so it's ready by the time the key is pressed
+    let table = table.get_or_insert_with(|| assets.load("loot/test_goblin.loot.json"));
+    if keyboard.just_pressed(KeyCode::F7) {
+        cmd.trigger(RollLootTable {
+            table: table.clone(),
+            times: 1000,
+            level: 1,
+        });
+    }
+}
@@ -1,93 +1,421 @@
-use bevy::{
-    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
-    prelude::*,
-};
+use bevy::prelude::*;
+use consumable::{ConsumableEffect, ConsumablePlugin};
+use crafting::CraftingPlugin;
+use currency::Coins;
+use dependencies::ItemAssets;
+use inventory::{Bulk, InventoryPlugin};
 use load_test::LoadTestPlugin;
-use serde::{de::Error, Deserialize, Serialize};
+use loader::ItemAssetLoader;
+use loot::LootPlugin;
+use model::ItemModel;
+use pickup::PickupPlugin;
+use registry::{ItemId, ItemRegistryPlugin};
+use runes::WeaponRunes;
+use serde::{Deserialize, Serialize};
+use shop::ShopPlugin;
+use weapon::{AttackStyle, WeaponGroup, WeaponTrait};
+
+#[cfg(debug_assertions)]
+use crate::asset_errors::toast_load_failures;
+use crate::{
+    asset_errors::{Validate, ValidationError},
+    health::damage::DamageType,
+};
 
+pub mod consumable;
+pub mod crafting;
+pub mod currency;
+pub mod dependencies;
+pub mod inventory;
 mod load_test;
+pub mod loader;
+pub mod loot;
+pub mod model;
+pub mod pickup;
+pub mod registry;
+pub mod runes;
+pub mod shop;
+pub mod weapon;
+
 pub struct ItemsPlugin;
 
 impl Plugin for ItemsPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<ItemType>();
         app.init_asset_loader::<ItemAssetLoader>();
-        app.add_plugins(LoadTestPlugin);
-    }
-}
-
-#[derive(Default)]
-pub struct ItemAssetLoader;
-
-impl AssetLoader for ItemAssetLoader {
-    type Asset = ItemType;
-    type Settings = ();
-    type Error = serde_json::Error;
-
-    async fn load(
-        &self,
-        reader: &mut dyn Reader,
-        _settings: &Self::Settings,
-        _load_context: &mut LoadContext<'_>,
-    ) -> Result<Self::Asset, Self::Error> {
-        let mut buffer = String::new();
-        if let Err(e) = reader.read_to_string(&mut buffer).await {
-            error!("Failed to load ");
-            return Err(serde_json::Error::custom(format!(
-                "Error with asset reader: {e}"
-            )));
-        }
-        serde_json::from_str::<Self::Asset>(buffer.as_str())
-    }
-    fn extensions(&self) -> &[&str] {
-        &[".json"]
+        #[cfg(debug_assertions)]
+        app.add_systems(Update, toast_load_failures::<ItemType>);
+        app.add_plugins((
+            ItemRegistryPlugin,
+            InventoryPlugin,
+            ConsumablePlugin,
+            CraftingPlugin,
+            PickupPlugin,
+            LootPlugin,
+            ShopPlugin,
+            LoadTestPlugin,
+        ));
     }
 }
+
 pub trait Item: Asset + Reflect + Clone + PartialEq {
-    fn get_name(&self) -> &String;
+    fn common(&self) -> &ItemCommon;
+
+    fn get_id(&self) -> &ItemId {
+        &self.common().id
+    }
+    fn get_name(&self) -> &String {
+        &self.common().name
+    }
+    fn get_bulk(&self) -> Bulk {
+        self.common().bulk
+    }
+    fn get_rarity(&self) -> Rarity {
+        self.common().rarity
+    }
+    fn get_level(&self) -> u32 {
+        self.common().level
+    }
+    fn get_price(&self) -> Coins {
+        self.common().price
+    }
+    fn get_model(&self) -> Option<&ItemModel> {
+        self.common().model.as_ref()
+    }
+}
+
+#[derive(
+    Debug,
+    Reflect,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Unique,
+}
+
+/// Per-copy state of an item, everything shared between copies stays on the `ItemType` asset
+#[derive(Debug, Reflect, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ItemState {
+    /// Durability lost so far
+    #[serde(default)]
+    pub wear: u32,
+    /// Only weapons make use of these
+    #[serde(default)]
+    pub runes: WeaponRunes,
+}
+
+impl ItemState {
+    /// Items without any durability never break
+    pub fn is_broken(&self, durability: u32) -> bool {
+        durability > 0 && self.wear >= durability
+    }
+}
+
+/// A single copy of an item, referenced by id so that it can be saved and resolved through the `ItemRegistry`
+#[derive(Debug, Reflect, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemInstance {
+    pub id: ItemId,
+    #[serde(default)]
+    pub state: ItemState,
 }
 
-#[derive(Debug, Reflect, Clone, PartialEq, Default)]
-pub struct ItemSlot(pub Option<Handle<ItemType>>);
+#[derive(Debug, Reflect, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ItemSlot(pub Option<ItemInstance>);
+
+impl ItemSlot {
+    pub fn new(id: impl Into<ItemId>) -> Self {
+        Self::with_state(id, ItemState::default())
+    }
+
+    pub fn with_state(id: impl Into<ItemId>, state: ItemState) -> Self {
+        Self(Some(ItemInstance {
+            id: id.into(),
+            state,
+        }))
+    }
+
+    pub fn id(&self) -> Option<&ItemId> {
+        self.0.as_ref().map(|instance| &instance.id)
+    }
+}
 
 #[derive(Debug, Asset, Reflect, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ItemType {
     Basic(BasicItem),
     Weapon(WeaponItem),
+    Armor(ArmorItem),
+    Shield(ShieldItem),
+    Consumable(ConsumableItem),
     // A boxed value does not impl Send which is necessary here
 }
 
+/// Fields every kind of item has, flattened into each item type
+#[derive(Debug, Reflect, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemCommon {
+    pub id: ItemId,
+    pub name: String,
+    #[serde(default)]
+    pub bulk: Bulk,
+    #[serde(default)]
+    pub rarity: Rarity,
+    #[serde(default)]
+    pub price: Coins,
+    #[serde(default)]
+    pub model: Option<ItemModel>,
+    #[serde(default)]
+    pub assets: ItemAssets,
+    #[serde(default)]
+    pub level: u32,
+}
+
 #[derive(Debug, Asset, Reflect, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BasicItem {
-    pub name: String,
+    #[serde(flatten)]
+    pub common: ItemCommon,
 }
 
 #[derive(Debug, Asset, Reflect, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WeaponItem {
-    pub name: String,
-    pub damage: u32,
+    #[serde(flatten)]
+    pub common: ItemCommon,
+    #[serde(default)]
+    pub hands: Hands,
+    pub group: WeaponGroup,
+    #[serde(default)]
+    pub traits: Vec<WeaponTrait>,
+    /// Size of the damage die, 8 for a d8
+    pub damage_die: u32,
+    pub damage_type: DamageType,
     pub durability: u32,
-    pub attack_style: String,
+    /// Asset path of the ammunition item for ranged weapons
+    #[serde(default)]
+    pub ammunition: Option<String>,
+    #[serde(default)]
+    pub attack_style: AttackStyle,
     pub attack_duration: f32,
 }
 
+/// How many hands a weapon needs to be wielded
+#[derive(Debug, Reflect, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Hands {
+    #[default]
+    One,
+    Two,
+}
+
+#[derive(Debug, Asset, Reflect, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArmorItem {
+    #[serde(flatten)]
+    pub common: ItemCommon,
+    /// Item bonus to AC
+    pub ac_bonus: u32,
+    /// Highest Dexterity modifier that still counts towards AC while wearing this
+    pub dex_cap: i16,
+    /// Applied to Strength and Dexterity based skill checks, as a negative number
+    #[serde(default)]
+    pub check_penalty: i16,
+    /// Speed penalty in feet
+    #[serde(default)]
+    pub speed_penalty: u32,
+    /// Strength modifier needed to offset the penalties
+    #[serde(default)]
+    pub strength: Option<i16>,
+}
+
+impl ArmorItem {
+    fn meets_strength(&self, strength: i16) -> bool {
+        self.strength.is_some_and(|required| strength >= required)
+    }
+
+    /// Meeting the Strength requirement removes the check penalty entirely
+    pub fn check_penalty_for(&self, strength: i16) -> i16 {
+        if self.meets_strength(strength) {
+            0
+        } else {
+            self.check_penalty
+        }
+    }
+
+    /// Meeting the Strength requirement reduces the speed penalty by 5 feet
+    pub fn speed_penalty_for(&self, strength: i16) -> u32 {
+        if self.meets_strength(strength) {
+            self.speed_penalty.saturating_sub(5)
+        } else {
+            self.speed_penalty
+        }
+    }
+}
+
+#[derive(Debug, Asset, Reflect, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShieldItem {
+    #[serde(flatten)]
+    pub common: ItemCommon,
+    /// Circumstance bonus to AC while the shield is raised
+    pub ac_bonus: u32,
+    /// Subtracted from any damage the shield takes
+    pub hardness: u32,
+    pub hp: u32,
+    /// The shield is broken once its HP drop to this value or below
+    pub break_threshold: u32,
+}
+
+/// Potions, elixirs, scrolls and anything else used up on activation
+#[derive(Debug, Asset, Reflect, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsumableItem {
+    #[serde(flatten)]
+    pub common: ItemCommon,
+    /// Actions it takes to activate, the effects apply once they have passed
+    pub actions: u32,
+    pub effects: Vec<ConsumableEffect>,
+    /// Animation to play while activating
+    #[serde(default)]
+    pub animation: Option<String>,
+}
+
 impl Item for ItemType {
-    fn get_name(&self) -> &String {
+    fn common(&self) -> &ItemCommon {
+        match self {
+            ItemType::Basic(basic_item) => &basic_item.common,
+            ItemType::Weapon(weapon_item) => &weapon_item.common,
+            ItemType::Armor(armor_item) => &armor_item.common,
+            ItemType::Shield(shield_item) => &shield_item.common,
+            ItemType::Consumable(consumable_item) => &consumable_item.common,
+        }
+    }
+}
+
+impl ItemType {
+    fn common_mut(&mut self) -> &mut ItemCommon {
         match self {
-            ItemType::Basic(basic_item) => basic_item.get_name(),
-            ItemType::Weapon(weapon_item) => weapon_item.get_name(),
+            ItemType::Basic(basic_item) => &mut basic_item.common,
+            ItemType::Weapon(weapon_item) => &mut weapon_item.common,
+            ItemType::Armor(armor_item) => &mut armor_item.common,
+            ItemType::Shield(shield_item) => &mut shield_item.common,
+            ItemType::Consumable(consumable_item) => &mut consumable_item.common,
+        }
+    }
+
+    /// Wear this item can take before it breaks, 0 for items that never break
+    pub fn durability(&self) -> u32 {
+        match self {
+            ItemType::Weapon(weapon_item) => weapon_item.durability,
+            _ => 0,
+        }
+    }
+}
+
+impl Validate for ItemType {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.get_id().trim().is_empty() {
+            return Err(ValidationError::new("id", "id must not be empty"));
+        }
+        match self {
+            ItemType::Basic(_) => Ok(()),
+            ItemType::Weapon(weapon) => {
+                if ![4, 6, 8, 10, 12].contains(&weapon.damage_die) {
+                    return Err(ValidationError::new(
+                        "damage_die",
+                        "damage_die must be 4, 6, 8, 10 or 12",
+                    ));
+                }
+                if weapon.attack_duration <= 0.0 {
+                    return Err(ValidationError::new(
+                        "attack_duration",
+                        "attack_duration must be > 0",
+                    ));
+                }
+                Ok(())
+            }
+            ItemType::Armor(armor) => {
+                if armor.check_penalty > 0 {
+                    return Err(ValidationError::new(
+                        "check_penalty",
+                        "check_penalty must be <= 0",
+                    ));
+                }
+                Ok(())
+            }
+            ItemType::Shield(shield) => {
+                if shield.break_threshold > shield.hp {
+                    return Err(ValidationError::new(
+                        "break_threshold",
+                        "break_threshold must be <= hp",
+                    ));
+                }
+                Ok(())
+            }
+            ItemType::Consumable(consumable) => {
+                if consumable.effects.is_empty() {
+                    return Err(ValidationError::new("effects", "effects must not be empty"));
+                }
+                for effect in consumable.effects.iter() {
+                    match effect {
+                        ConsumableEffect::Heal { sides: 0, .. } => {
+                            return Err(ValidationError::new("sides", "sides must be > 0"));
+                        }
+                        ConsumableEffect::TemporaryHp {
+                            duration: Some(secs),
+                            ..
+                        } if !(secs.is_finite() && *secs > 0.0) => {
+                            return Err(ValidationError::new(
+                                "duration",
+                                "temporary HP duration must be finite and > 0",
+                            ));
+                        }
+                        ConsumableEffect::Buff(buff) if buff.duration <= 0.0 => {
+                            return Err(ValidationError::new(
+                                "duration",
+                                "buff duration must be > 0",
+                            ));
+                        }
+                        _ => (),
+                    }
+                }
+                Ok(())
+            }
         }
     }
 }
 
 impl Item for BasicItem {
-    fn get_name(&self) -> &String {
-        &self.name
+    fn common(&self) -> &ItemCommon {
+        &self.common
     }
 }
 
 impl Item for WeaponItem {
-    fn get_name(&self) -> &String {
-        &self.name
+    fn common(&self) -> &ItemCommon {
+        &self.common
+    }
+}
+
+impl Item for ArmorItem {
+    fn common(&self) -> &ItemCommon {
+        &self.common
+    }
+}
+
+impl Item for ShieldItem {
+    fn common(&self) -> &ItemCommon {
+        &self.common
+    }
+}
+
+impl Item for ConsumableItem {
+    fn common(&self) -> &ItemCommon {
+        &self.common
     }
 }
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A glTF model shown on whoever has the item equipped
+#[derive(Debug, Reflect, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemModel {
+    /// Asset path of the glTF file, its first scene is spawned
+    pub path: String,
+    /// Name of the bone the model is attached to, characters can remap it with `Sockets`
+    pub socket: String,
+    /// Relative to the bone
+    #[serde(default)]
+    pub offset: [f32; 3],
+    /// Euler angles (XYZ) in degrees
+    #[serde(default)]
+    pub rotation: [f32; 3],
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+impl ItemModel {
+    pub fn transform(&self) -> Transform {
+        let [x, y, z] = self.rotation.map(f32::to_radians);
+        Transform {
+            translation: Vec3::from_array(self.offset),
+            rotation: Quat::from_euler(EulerRot::XYZ, x, y, z),
+            scale: Vec3::splat(self.scale),
+        }
+    }
+}
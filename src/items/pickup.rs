@@ -0,0 +1,107 @@
+use std::f32::consts::TAU;
+
+use avian3d::prelude::{Collider, CollidingEntities, Sensor};
+use bevy::prelude::*;
+
+use crate::health::death::LootDropped;
+
+use super::{ItemState, ItemType};
+
+/// Radius of the sensor that decides whether something is close enough to pick an item up
+pub const PICKUP_RADIUS: f32 = 1.5;
+/// Distance between dropped loot and the spot it was dropped at
+const LOOT_SCATTER: f32 = 0.6;
+
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldItemAssets>();
+        app.add_observer(spawn_world_item);
+        app.add_observer(spawn_dropped_loot);
+    }
+}
+
+/// An item lying around in the world, waiting to be picked up
+#[derive(Debug, Component, Clone)]
+#[require(Sensor, CollidingEntities)]
+pub struct WorldItem {
+    pub item: Handle<ItemType>,
+    pub state: ItemState,
+    pub count: u32,
+}
+
+/// Trigger to place an item in the world
+#[derive(Debug, Event, Clone)]
+pub struct SpawnWorldItem {
+    pub item: WorldItem,
+    pub position: Vec3,
+}
+
+impl SpawnWorldItem {
+    pub fn new(item: Handle<ItemType>, count: u32, position: Vec3) -> Self {
+        Self {
+            item: WorldItem {
+                item,
+                state: ItemState::default(),
+                count,
+            },
+            position,
+        }
+    }
+}
+
+/// Placeholder visuals shared by every world item until items get their own models
+#[derive(Resource)]
+struct WorldItemAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for WorldItemAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::from_length(0.3));
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(Color::srgb(0.9, 0.75, 0.3));
+        Self { mesh, material }
+    }
+}
+
+fn spawn_world_item(
+    trigger: Trigger<SpawnWorldItem>,
+    visuals: Res<WorldItemAssets>,
+    mut cmd: Commands,
+) {
+    let event = trigger.event();
+    cmd.spawn((
+        Name::new("World Item"),
+        event.item.clone(),
+        Transform::from_translation(event.position),
+        Collider::sphere(PICKUP_RADIUS),
+        Mesh3d(visuals.mesh.clone()),
+        MeshMaterial3d(visuals.material.clone()),
+    ));
+}
+
+fn spawn_dropped_loot(trigger: Trigger<LootDropped>, mut cmd: Commands) {
+    let count = trigger.items.len();
+    for (i, stack) in trigger.items.iter().enumerate() {
+        // spread the drops in a circle so they don't all end up inside each other
+        let offset = if count > 1 {
+            Quat::from_rotation_y(TAU * i as f32 / count as f32) * Vec3::X * LOOT_SCATTER
+        } else {
+            Vec3::ZERO
+        };
+        cmd.trigger(SpawnWorldItem {
+            item: WorldItem {
+                item: stack.item.clone(),
+                state: stack.state.clone(),
+                count: stack.count,
+            },
+            position: trigger.position + offset,
+        });
+    }
+}
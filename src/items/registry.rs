@@ -0,0 +1,170 @@
+use bevy::{
+    asset::{LoadState, LoadedFolder},
+    prelude::*,
+    utils::hashbrown::HashMap,
+};
+
+use crate::{
+    asset_errors::{DataAssetError, DataAssetErrorKind},
+    toast::ToastEvent,
+};
+
+use super::{Item, ItemType};
+
+/// Stable identifier of an item, used instead of asset paths anywhere items are referenced from data
+pub type ItemId = String;
+
+const ITEM_FOLDER: &str = "item";
+
+pub struct ItemRegistryPlugin;
+
+impl Plugin for ItemRegistryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ItemRegistry>();
+        app.add_systems(Startup, load_item_folder);
+        app.add_systems(
+            Update,
+            populate_registry.run_if(|registry: Res<ItemRegistry>| !registry.is_ready()),
+        );
+    }
+}
+
+/// Every item found under `assets/item/`, keyed by its id
+#[derive(Resource, Default)]
+pub struct ItemRegistry {
+    folder: Handle<LoadedFolder>,
+    items: HashMap<ItemId, Handle<ItemType>>,
+    ready: bool,
+}
+
+impl ItemRegistry {
+    pub fn get(&self, id: &str) -> Option<&Handle<ItemType>> {
+        self.items.get(id)
+    }
+
+    #[cfg(test)]
+    pub fn insert(&mut self, id: impl Into<ItemId>, item: Handle<ItemType>) {
+        self.items.insert(id.into(), item);
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.items.contains_key(id)
+    }
+
+    /// Whether every item in the folder has finished loading (or failed to)
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Logs and toasts a missing reference, returns whether `id` exists
+    pub fn check_reference(&self, id: &str, referenced_by: &str, cmd: &mut Commands) -> bool {
+        if self.contains(id) {
+            return true;
+        }
+        let message = format!("{referenced_by} references unknown item '{id}'");
+        error!("{message}");
+        cmd.trigger(ToastEvent(message));
+        false
+    }
+}
+
+/// Data assets that reference items by id, checked against the registry with `validate_item_references`
+pub trait ItemReferences {
+    /// Every referenced item id along with the array element it is in, such as `("entries", 2)`
+    fn item_references(&self) -> Vec<((&'static str, usize), &ItemId)>;
+}
+
+/// Reports the item ids referenced by loaded `A` assets that aren't in the registry. Assets loaded before the registry
+/// is ready are checked as soon as it is
+pub fn validate_item_references<A: Asset + ItemReferences>(
+    mut events: EventReader<AssetEvent<A>>,
+    mut pending: Local<Vec<AssetId<A>>>,
+    registry: Res<ItemRegistry>,
+    data: Res<Assets<A>>,
+    assets: Res<AssetServer>,
+    mut cmd: Commands,
+) {
+    for event in events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            pending.push(*id);
+        }
+    }
+    if !registry.is_ready() {
+        return;
+    }
+    for id in pending.drain(..) {
+        let Some(asset) = data.get(id) else {
+            continue;
+        };
+        let path = assets.get_path(id).unwrap_or_default();
+        for ((array, index), item) in asset.item_references() {
+            if registry.contains(item) {
+                continue;
+            }
+            let error = DataAssetError::new(
+                path.path(),
+                DataAssetErrorKind::Invalid(format!("{array}[{index}]: unknown item '{item}'")),
+            );
+            error!("{error}");
+            cmd.trigger(ToastEvent(error.to_string()));
+        }
+    }
+}
+
+/// Triggered once when the `ItemRegistry` is populated
+#[derive(Debug, Event)]
+pub struct ItemRegistryReady;
+
+fn load_item_folder(assets: Res<AssetServer>, mut registry: ResMut<ItemRegistry>) {
+    registry.folder = assets.load_folder(ITEM_FOLDER);
+}
+
+fn populate_registry(
+    mut registry: ResMut<ItemRegistry>,
+    folders: Res<Assets<LoadedFolder>>,
+    items: Res<Assets<ItemType>>,
+    assets: Res<AssetServer>,
+    mut cmd: Commands,
+) {
+    let Some(folder) = folders.get(registry.folder.id()) else {
+        return;
+    };
+    let handles: Vec<Handle<ItemType>> = folder
+        .handles
+        .iter()
+        .filter_map(|h| h.clone().try_typed::<ItemType>().ok())
+        .collect();
+    let still_loading = handles.iter().any(|h| {
+        !matches!(
+            assets.get_load_state(h.id()),
+            Some(LoadState::Loaded) | Some(LoadState::Failed(_))
+        )
+    });
+    if still_loading {
+        return;
+    }
+
+    let mut registered: HashMap<ItemId, Handle<ItemType>> = HashMap::new();
+    for handle in handles {
+        // failed items are already reported by the asset server
+        let Some(item) = items.get(handle.id()) else {
+            continue;
+        };
+        let id = item.get_id();
+        if let Some(existing) = registered.get(id) {
+            let message = format!(
+                "Duplicate item id '{id}' in '{}' and '{}'",
+                assets.get_path(existing.id()).unwrap_or_default(),
+                assets.get_path(handle.id()).unwrap_or_default()
+            );
+            error!("{message}");
+            cmd.trigger(ToastEvent(message));
+            continue;
+        }
+        registered.insert(id.clone(), handle);
+    }
+    info!("Item registry ready with {} items", registered.len());
+    registry.items = registered;
+    registry.ready = true;
+    cmd.trigger(ItemRegistryReady);
+}
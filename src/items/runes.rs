@@ -0,0 +1,207 @@
+use std::fmt::Display;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::health::damage::DamageType;
+
+/// Highest grade of potency and striking runes (+3 and major striking)
+pub const MAX_RUNE_GRADE: u32 = 3;
+/// Die size of the extra damage from energy property runes
+pub const PROPERTY_RUNE_DIE: u32 = 6;
+
+/// Runes etched onto a single weapon. Potency adds an item bonus to attack rolls, striking adds weapon damage dice and
+/// every point of potency opens a slot for a property rune
+#[derive(Debug, Reflect, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct WeaponRunes {
+    #[serde(default)]
+    pub potency: u32,
+    #[serde(default)]
+    pub striking: u32,
+    #[serde(default)]
+    pub property: Vec<PropertyRune>,
+}
+
+#[derive(Debug, Reflect, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PropertyRune {
+    Corrosive,
+    Flaming,
+    Frost,
+    Shock,
+    Thundering,
+}
+
+impl PropertyRune {
+    /// Type of the extra damage die this rune adds to every strike
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            PropertyRune::Corrosive => DamageType::Acid,
+            PropertyRune::Flaming => DamageType::Fire,
+            PropertyRune::Frost => DamageType::Cold,
+            PropertyRune::Shock => DamageType::Electricity,
+            PropertyRune::Thundering => DamageType::Sonic,
+        }
+    }
+}
+
+#[derive(Debug, Reflect, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rune {
+    Potency(u32),
+    Striking(u32),
+    Property(PropertyRune),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuneError {
+    /// Grade outside of 1..=`MAX_RUNE_GRADE`
+    InvalidGrade,
+    /// The weapon already has a fundamental rune of this grade or higher
+    NotAnUpgrade,
+    /// Every property rune slot is taken, or there is no potency rune
+    NoPropertySlot,
+    AlreadyEtched,
+    NotEtched,
+    /// Property runes can't stay behind without the potency rune that holds them
+    PotencyInUse,
+}
+
+impl Display for RuneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            RuneError::InvalidGrade => "invalid rune grade",
+            RuneError::NotAnUpgrade => "the weapon already has a rune at least as strong",
+            RuneError::NoPropertySlot => "no free property rune slot",
+            RuneError::AlreadyEtched => "the rune is already etched onto the weapon",
+            RuneError::NotEtched => "the weapon doesn't have that rune",
+            RuneError::PotencyInUse => "the property runes depend on the potency rune",
+        };
+        f.write_str(message)
+    }
+}
+
+impl WeaponRunes {
+    pub fn free_property_slots(&self) -> usize {
+        (self.potency as usize).saturating_sub(self.property.len())
+    }
+
+    pub fn etch(&mut self, rune: Rune) -> Result<(), RuneError> {
+        match rune {
+            Rune::Potency(grade) | Rune::Striking(grade)
+                if grade == 0 || grade > MAX_RUNE_GRADE =>
+            {
+                Err(RuneError::InvalidGrade)
+            }
+            Rune::Potency(grade) if grade <= self.potency => Err(RuneError::NotAnUpgrade),
+            Rune::Potency(grade) => {
+                self.potency = grade;
+                Ok(())
+            }
+            Rune::Striking(grade) if grade <= self.striking => Err(RuneError::NotAnUpgrade),
+            Rune::Striking(grade) => {
+                self.striking = grade;
+                Ok(())
+            }
+            Rune::Property(property) if self.property.contains(&property) => {
+                Err(RuneError::AlreadyEtched)
+            }
+            Rune::Property(_) if self.free_property_slots() == 0 => Err(RuneError::NoPropertySlot),
+            Rune::Property(property) => {
+                self.property.push(property);
+                Ok(())
+            }
+        }
+    }
+
+    /// Takes a rune off this weapon, fundamental runes are removed whole
+    pub fn remove(&mut self, rune: Rune) -> Result<Rune, RuneError> {
+        match rune {
+            Rune::Potency(_) if self.potency == 0 => Err(RuneError::NotEtched),
+            Rune::Potency(_) if !self.property.is_empty() => Err(RuneError::PotencyInUse),
+            Rune::Potency(_) => Ok(Rune::Potency(std::mem::take(&mut self.potency))),
+            Rune::Striking(_) if self.striking == 0 => Err(RuneError::NotEtched),
+            Rune::Striking(_) => Ok(Rune::Striking(std::mem::take(&mut self.striking))),
+            Rune::Property(property) => {
+                let index = self
+                    .property
+                    .iter()
+                    .position(|p| *p == property)
+                    .ok_or(RuneError::NotEtched)?;
+                Ok(Rune::Property(self.property.remove(index)))
+            }
+        }
+    }
+
+    /// Moves a rune onto another weapon. Nothing changes on either weapon if the transfer fails
+    pub fn transfer(&mut self, to: &mut WeaponRunes, rune: Rune) -> Result<(), RuneError> {
+        let mut from = self.clone();
+        let rune = from.remove(rune)?;
+        to.etch(rune)?;
+        *self = from;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::items::ItemType;
+
+    use super::*;
+
+    #[test]
+    fn potency_caps_property_slots() {
+        let mut runes = WeaponRunes::default();
+        assert_eq!(
+            runes.etch(Rune::Property(PropertyRune::Flaming)),
+            Err(RuneError::NoPropertySlot)
+        );
+        runes.etch(Rune::Potency(1)).unwrap();
+        runes.etch(Rune::Property(PropertyRune::Flaming)).unwrap();
+        assert_eq!(
+            runes.etch(Rune::Property(PropertyRune::Frost)),
+            Err(RuneError::NoPropertySlot)
+        );
+        runes.etch(Rune::Potency(2)).unwrap();
+        runes.etch(Rune::Property(PropertyRune::Frost)).unwrap();
+        assert_eq!(runes.free_property_slots(), 0);
+        assert_eq!(runes.remove(Rune::Potency(2)), Err(RuneError::PotencyInUse));
+    }
+
+    #[test]
+    fn failed_transfer_leaves_both_weapons_alone() {
+        let mut from = WeaponRunes {
+            potency: 1,
+            property: vec![PropertyRune::Shock],
+            ..default()
+        };
+        let mut to = WeaponRunes::default();
+        assert_eq!(
+            from.transfer(&mut to, Rune::Property(PropertyRune::Shock)),
+            Err(RuneError::NoPropertySlot)
+        );
+        assert_eq!(from.property, vec![PropertyRune::Shock]);
+        assert_eq!(to, WeaponRunes::default());
+    }
+
+    #[test]
+    fn striking_adds_weapon_dice() {
+        let weapon: ItemType =
+            serde_json::from_str(include_str!("../../assets/item/test_weapon.item.json")).unwrap();
+        let ItemType::Weapon(weapon) = weapon else {
+            panic!("test_weapon is not a weapon");
+        };
+        for (striking, dice) in [(0, 1), (1, 2), (2, 3), (3, 4)] {
+            let runes = WeaponRunes {
+                striking,
+                ..default()
+            };
+            assert_eq!(weapon.stats(&runes).dice, dice);
+        }
+        let mut runes = WeaponRunes::default();
+        runes.etch(Rune::Striking(2)).unwrap();
+        assert_eq!(runes.etch(Rune::Striking(1)), Err(RuneError::NotAnUpgrade));
+        assert_eq!(
+            runes.etch(Rune::Striking(MAX_RUNE_GRADE + 1)),
+            Err(RuneError::InvalidGrade)
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+
+use crate::{rpg_data::core::CoreData, toast::ToastEvent};
+
+use super::{
+    currency::{Coins, Wallet},
+    inventory::{EquippedBulk, Inventory},
+    registry::{ItemId, ItemRegistry},
+    Item, ItemType,
+};
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(buy_item);
+        app.add_observer(sell_item);
+        app.add_observer(toast_transaction);
+        app.add_observer(toast_failed_transaction);
+    }
+}
+
+/// Both sides of a trade, the Bulk limit only applies to characters with `CoreData`
+type Traders<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Inventory,
+        &'static mut Wallet,
+        Option<&'static CoreData>,
+        Option<&'static EquippedBulk>,
+    ),
+>;
+
+/// An NPC that trades the items in its `Inventory`. Prices are the item's price times the multiplier for the direction
+/// of the trade
+#[derive(Debug, Component, Clone, Copy)]
+#[require(Inventory, Wallet)]
+pub struct Merchant {
+    /// Applied when the merchant sells to a customer
+    pub sell_multiplier: f32,
+    /// Applied when the merchant buys from a customer
+    pub buy_multiplier: f32,
+}
+
+impl Default for Merchant {
+    /// PF2e merchants sell at full price and buy at half price
+    fn default() -> Self {
+        Self {
+            sell_multiplier: 1.0,
+            buy_multiplier: 0.5,
+        }
+    }
+}
+
+/// Trigger on a customer with an `Inventory` and a `Wallet` to buy items from a merchant
+#[derive(Debug, Event, Clone)]
+pub struct BuyItem {
+    pub merchant: Entity,
+    pub item: ItemId,
+    pub count: u32,
+}
+
+/// Trigger on a customer with an `Inventory` and a `Wallet` to sell items to a merchant
+#[derive(Debug, Event, Clone)]
+pub struct SellItem {
+    pub merchant: Entity,
+    pub item: ItemId,
+    pub count: u32,
+}
+
+/// Triggered once items and coins have changed hands, this is what UI, saving and quests should observe
+#[derive(Debug, Event, Clone)]
+pub struct Transaction {
+    pub buyer: Entity,
+    pub seller: Entity,
+    pub item: ItemId,
+    pub count: u32,
+    /// Total paid for all `count` items
+    pub price: Coins,
+}
+
+/// Triggered when a trade is refused, with the reason
+#[derive(Debug, Event, Clone)]
+pub struct TransactionFailed {
+    pub buyer: Entity,
+    pub seller: Entity,
+    pub item: ItemId,
+    pub reason: String,
+}
+
+fn buy_item(
+    trigger: Trigger<BuyItem>,
+    q_merchants: Query<&Merchant>,
+    mut q: Traders,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let Ok(merchant) = q_merchants.get(trigger.merchant) else {
+        return;
+    };
+    let Some(handle) = registry.get(&trigger.item) else {
+        registry.check_reference(&trigger.item, "Buy request", &mut cmd);
+        return;
+    };
+    let Some(item) = items.get(handle.id()) else {
+        return;
+    };
+    let price = item
+        .get_price()
+        .scaled(merchant.sell_multiplier)
+        .times(trigger.count);
+    trade(
+        &mut q,
+        trigger.merchant,
+        trigger.entity(),
+        (&trigger.item, handle, item),
+        (trigger.count, price),
+        &items,
+        &mut cmd,
+    );
+}
+
+fn sell_item(
+    trigger: Trigger<SellItem>,
+    q_merchants: Query<&Merchant>,
+    mut q: Traders,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let Ok(merchant) = q_merchants.get(trigger.merchant) else {
+        return;
+    };
+    let Some(handle) = registry.get(&trigger.item) else {
+        registry.check_reference(&trigger.item, "Sell request", &mut cmd);
+        return;
+    };
+    let Some(item) = items.get(handle.id()) else {
+        return;
+    };
+    let price = item
+        .get_price()
+        .scaled(merchant.buy_multiplier)
+        .times(trigger.count);
+    trade(
+        &mut q,
+        trigger.entity(),
+        trigger.merchant,
+        (&trigger.item, handle, item),
+        (trigger.count, price),
+        &items,
+        &mut cmd,
+    );
+}
+
+/// Moves `count` copies of an item from the seller to the buyer in exchange for `price`, all or nothing
+fn trade(
+    q: &mut Traders,
+    seller: Entity,
+    buyer: Entity,
+    (id, handle, item): (&ItemId, &Handle<ItemType>, &ItemType),
+    (count, price): (u32, Coins),
+    items: &Assets<ItemType>,
+    cmd: &mut Commands,
+) {
+    if buyer == seller {
+        cmd.trigger(TransactionFailed {
+            buyer,
+            seller,
+            item: id.clone(),
+            reason: "can't trade with yourself".to_owned(),
+        });
+        return;
+    }
+    let Ok(
+        [(mut seller_inventory, mut seller_wallet, ..), (mut buyer_inventory, mut buyer_wallet, buyer_core, buyer_equipped)],
+    ) = q.get_many_mut([seller, buyer])
+    else {
+        cmd.trigger(TransactionFailed {
+            buyer,
+            seller,
+            item: id.clone(),
+            reason: "both sides need an inventory and a wallet".to_owned(),
+        });
+        return;
+    };
+    let reason = if count == 0 {
+        Some("nothing to trade")
+    } else if seller_inventory.count(handle) < count {
+        Some("not enough items")
+    } else if !buyer_inventory.fits(buyer_core, buyer_equipped, item, count, items) {
+        Some("too heavy to carry")
+    } else if !buyer_wallet.pay(price) {
+        Some("not enough coins")
+    } else {
+        None
+    };
+    if let Some(reason) = reason {
+        cmd.trigger(TransactionFailed {
+            buyer,
+            seller,
+            item: id.clone(),
+            reason: reason.to_owned(),
+        });
+        return;
+    }
+    seller_wallet.receive(price);
+    for _ in 0..count {
+        if let Some(state) = seller_inventory.take_one(handle) {
+            buyer_inventory.add_with_state(handle.clone(), state, 1);
+        }
+    }
+    cmd.trigger(Transaction {
+        buyer,
+        seller,
+        item: id.clone(),
+        count,
+        price,
+    });
+}
+
+fn toast_transaction(trigger: Trigger<Transaction>, mut cmd: Commands) {
+    cmd.trigger(ToastEvent(format!(
+        "Traded {}x {} for {}",
+        trigger.count, trigger.item, trigger.price
+    )));
+}
+
+fn toast_failed_transaction(trigger: Trigger<TransactionFailed>, mut cmd: Commands) {
+    cmd.trigger(ToastEvent(format!(
+        "Can't trade {}: {}",
+        trigger.item, trigger.reason
+    )));
+}
@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    health::damage::{DamageComponent, DamageType},
+    rpg_data::dice::DiceRng,
+};
+
+use super::{
+    runes::{WeaponRunes, PROPERTY_RUNE_DIE},
+    WeaponItem,
+};
+
+#[derive(Debug, Reflect, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WeaponGroup {
+    Axe,
+    Bow,
+    Brawling,
+    Club,
+    Crossbow,
+    Dart,
+    Flail,
+    Hammer,
+    Knife,
+    Pick,
+    Polearm,
+    Shield,
+    Sling,
+    Spear,
+    Sword,
+}
+
+/// The PF2e weapon traits the combat code knows about. Dice traits hold the die size, `Deadly(10)` is deadly d10
+#[derive(Debug, Reflect, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WeaponTrait {
+    /// Lower multiple attack penalty
+    Agile,
+    /// Attack rolls can use Dexterity instead of Strength
+    Finesse,
+    /// Twice the usual reach
+    Reach,
+    /// +1 to hit another target during the same swing
+    Sweep,
+    /// Extra damage per die on the second and later attacks of a turn
+    Forceful,
+    /// An extra die of this size on a critical hit
+    Deadly(u32),
+    /// On a critical hit the damage dice become this size, plus one more die
+    Fatal(u32),
+    /// Damage die while wielded in two hands
+    TwoHand(u32),
+}
+
+/// How the weapon is swung, decides the shape of the hitbox
+#[derive(Debug, Reflect, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AttackStyle {
+    /// Wide horizontal arc
+    #[default]
+    Slash,
+    /// Narrow and straight ahead
+    Thrust,
+    /// Short and heavy
+    Smash,
+}
+
+/// Damage of a single strike, rolled up front so that the hitbox can apply either
+#[derive(Debug, Clone)]
+pub struct StrikeDamage {
+    pub normal: Vec<DamageComponent>,
+    pub critical: Vec<DamageComponent>,
+}
+
+/// What a weapon's runes add on top of the base `WeaponItem`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeaponStats {
+    /// Item bonus to attack rolls
+    pub attack_bonus: i32,
+    /// Number of weapon damage dice
+    pub dice: u32,
+    /// One extra die of each of these types
+    pub extra_damage: Vec<DamageType>,
+}
+
+impl WeaponItem {
+    pub fn has_trait(&self, weapon_trait: WeaponTrait) -> bool {
+        self.traits.contains(&weapon_trait)
+    }
+
+    fn dice_trait(&self, matches: impl Fn(&WeaponTrait) -> Option<u32>) -> Option<u32> {
+        self.traits.iter().find_map(matches)
+    }
+
+    pub fn die_size(&self, two_handed: bool) -> u32 {
+        let two_hand = self.dice_trait(|t| match t {
+            WeaponTrait::TwoHand(die) => Some(*die),
+            _ => None,
+        });
+        match two_hand {
+            Some(die) if two_handed => die,
+            _ => self.damage_die,
+        }
+    }
+
+    /// The effective stats of a copy of this weapon with the given runes etched on
+    pub fn stats(&self, runes: &WeaponRunes) -> WeaponStats {
+        WeaponStats {
+            attack_bonus: runes.potency as i32,
+            dice: 1 + runes.striking,
+            extra_damage: runes.property.iter().map(|p| p.damage_type()).collect(),
+        }
+    }
+
+    /// Rolls both the normal and the critical damage of a strike. `attacks_this_turn` counts the attacks made before
+    /// this one
+    pub fn roll_damage(
+        &self,
+        runes: &WeaponRunes,
+        two_handed: bool,
+        strength: i16,
+        attacks_this_turn: u32,
+        rng: &mut DiceRng,
+    ) -> StrikeDamage {
+        let stats = self.stats(runes);
+        let dice = stats.dice;
+        let forceful = if self.has_trait(WeaponTrait::Forceful) {
+            attacks_this_turn.min(2) as i32 * dice as i32
+        } else {
+            0
+        };
+        let flat = strength as i32 + forceful;
+        let roll =
+            |rng: &mut DiceRng, die: u32| (rng.roll_dice(dice, die) as i32 + flat).max(1) as u32;
+
+        let normal = roll(rng, self.die_size(two_handed));
+        let fatal = self.dice_trait(|t| match t {
+            WeaponTrait::Fatal(die) => Some(*die),
+            _ => None,
+        });
+        let mut critical = match fatal {
+            Some(die) => roll(rng, die) * 2 + rng.roll(die),
+            None => normal * 2,
+        };
+        let deadly = self.dice_trait(|t| match t {
+            WeaponTrait::Deadly(die) => Some(*die),
+            _ => None,
+        });
+        if let Some(die) = deadly {
+            // greater and major striking runes add more deadly dice
+            critical += rng.roll_dice(dice.saturating_sub(1).max(1), die);
+        }
+
+        let mut damage = StrikeDamage {
+            normal: vec![DamageComponent::new(normal, self.damage_type)],
+            critical: vec![DamageComponent::new(critical, self.damage_type)],
+        };
+        for damage_type in stats.extra_damage {
+            let amount = rng.roll(PROPERTY_RUNE_DIE);
+            damage
+                .normal
+                .push(DamageComponent::new(amount, damage_type));
+            damage
+                .critical
+                .push(DamageComponent::new(amount * 2, damage_type));
+        }
+        damage
+    }
+}
@@ -1,11 +1,25 @@
 use std::time::Duration;
 
 use super::{LevelDescription, LevelState};
-use avian3d::prelude::{ColliderConstructor, ColliderConstructorHierarchy, RigidBody};
+use avian3d::prelude::{Collider, ColliderConstructor, ColliderConstructorHierarchy, RigidBody};
 use bevy::{pbr::CascadeShadowConfigBuilder, prelude::*};
 use bevy_asset_loader::asset_collection::AssetCollection;
 use bevy_kira_audio::{Audio, AudioControl};
 
+use crate::{
+    combat::{Faction, Hurtbox},
+    health::{display::ShowHealthBar, DieOnHealthZero, Health},
+    items::{
+        currency::{Coins, Wallet},
+        inventory::Inventory,
+        loot::{LootChest, LootTableOnDeath},
+        pickup::{SpawnWorldItem, PICKUP_RADIUS},
+        shop::Merchant,
+    },
+};
+
+const DUMMY_HEIGHT: f32 = 1.6;
+
 pub struct LevelFeatureGarden;
 
 impl LevelDescription<LevelState> for LevelFeatureGarden {
@@ -26,6 +40,7 @@ fn load_level(
     assets: Res<GardenAssets>,
     gltf: Res<Assets<Gltf>>,
     audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
 ) {
     let Some(scene) = gltf
         .get(assets.level.id())
@@ -40,6 +55,68 @@ fn load_level(
         RigidBody::Static,
         ColliderConstructorHierarchy::new(ColliderConstructor::ConvexHullFromMesh),
     ));
+    cmd.trigger(SpawnWorldItem::new(
+        asset_server.load("item/test_shield.item.json"),
+        1,
+        Vec3::new(3.0, 0.5, 2.0),
+    ));
+    cmd.trigger(SpawnWorldItem::new(
+        asset_server.load("item/test_torch.item.toml"),
+        2,
+        Vec3::new(-3.0, 0.5, 2.0),
+    ));
+    cmd.spawn((
+        Name::new("Chest"),
+        LootChest {
+            table: asset_server.load("loot/test_goblin.loot.json"),
+            level: 1,
+        },
+        Collider::sphere(PICKUP_RADIUS),
+        SceneRoot(
+            asset_server
+                .load(GltfAssetLabel::Scene(0).from_asset("kenney_prototype_kit/crate.glb")),
+        ),
+        Transform::from_xyz(0.0, 0.0, 5.0),
+    ));
+    let mut stock = Inventory::default();
+    stock.add(asset_server.load("item/test_potion.item.json"), 5);
+    stock.add(asset_server.load("item/test_dagger.item.ron"), 2);
+    cmd.spawn((
+        Name::new("Merchant"),
+        Merchant::default(),
+        stock,
+        Wallet(Coins::gold(50)),
+        SceneRoot(
+            asset_server
+                .load(GltfAssetLabel::Scene(0).from_asset("kenney_prototype_kit/figurine.glb")),
+        ),
+        Transform::from_xyz(-4.0, 0.0, 5.0),
+    ));
+    cmd.spawn((
+        Name::new("Training Dummy"),
+        Faction::Hostile,
+        Hurtbox,
+        Health {
+            current: 20,
+            max: 20,
+            ..default()
+        },
+        DieOnHealthZero,
+        LootTableOnDeath(asset_server.load("loot/test_goblin.loot.json")),
+        ShowHealthBar::default(),
+        RigidBody::Static,
+        Collider::cylinder(0.4, DUMMY_HEIGHT),
+        Transform::from_xyz(4.0, DUMMY_HEIGHT / 2.0, 5.0),
+        InheritedVisibility::default(),
+    ))
+    .with_children(|cmd| {
+        cmd.spawn((
+            SceneRoot(asset_server.load(
+                GltfAssetLabel::Scene(0).from_asset("kenney_prototype_kit/figurine-large.glb"),
+            )),
+            Transform::from_xyz(0.0, -DUMMY_HEIGHT / 2.0, 0.0),
+        ));
+    });
     audio
         .play(assets.bgm.clone_weak())
         .looped()
@@ -13,6 +13,7 @@ use bevy_kira_audio::AudioPlugin;
 use bevy_tnua::prelude::TnuaControllerPlugin;
 use bevy_tnua_avian3d::TnuaAvian3dPlugin;
 use bevy_tween::DefaultTweenPlugins;
+use combat::CombatPlugin;
 use game_states::GameStatesPlugin;
 use health::HealthPlugin;
 use items::ItemsPlugin;
@@ -24,6 +25,8 @@ use seldom_state::StateMachinePlugin;
 use settings::SettingsPlugin;
 use toast::ToastPlugin;
 
+mod asset_errors;
+mod combat;
 mod game_states;
 mod health;
 mod items;
@@ -69,6 +72,7 @@ fn main() {
                 HealthPlugin,
                 PostProcessPlugin,
                 RpgDataPlugin,
+                CombatPlugin,
             ),
         ))
         .add_systems(Update, quit_on_f8)
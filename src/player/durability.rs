@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    combat::HitLanded,
+    items::{
+        registry::{ItemId, ItemRegistry},
+        Item, ItemType,
+    },
+    rpg_data::{
+        core::{CoreData, Skills},
+        dice::{DegreeOfSuccess, DiceRng},
+    },
+    toast::ToastEvent,
+};
+
+use super::{
+    equipment::{EquipSlot, Equipment},
+    inputs::Inputs,
+    PlayerRoot,
+};
+
+/// Wear a weapon takes for every hit it lands
+pub const WEAR_PER_HIT: u32 = 1;
+pub const REPAIR_DC: i32 = 15;
+
+pub struct DurabilityPlugin;
+
+impl Plugin for DurabilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, repair_on_input);
+        app.add_observer(wear_on_hit);
+        app.add_observer(repair_item);
+    }
+}
+
+/// Trigger on an entity with `Equipment` to attempt a Crafting check to repair the item in one of its slots
+#[derive(Debug, Event, Clone, Copy)]
+pub struct RepairItem(pub EquipSlot);
+
+/// Triggered on the wielder when one of their equipped items breaks
+#[derive(Debug, Event, Clone)]
+pub struct ItemBroken(pub ItemId);
+
+fn wear_on_hit(
+    trigger: Trigger<HitLanded>,
+    mut q: Query<&mut Equipment>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let Some(attacker) = trigger.attacker else {
+        return;
+    };
+    let Ok(mut equipment) = q.get_mut(attacker) else {
+        return;
+    };
+    let Some(instance) = equipment.main_hand.0.as_mut() else {
+        return;
+    };
+    let Some(item) = registry
+        .get(&instance.id)
+        .and_then(|handle| items.get(handle.id()))
+    else {
+        return;
+    };
+    if item.durability() == 0 || instance.state.is_broken(item.durability()) {
+        return;
+    }
+    instance.state.wear += WEAR_PER_HIT;
+    if instance.state.is_broken(item.durability()) {
+        cmd.trigger_targets(ItemBroken(instance.id.clone()), attacker);
+        cmd.trigger(ToastEvent(format!("{} broke", item.get_name())));
+    }
+}
+
+/// PF2e Repair: a success restores 5 durability plus 5 per proficiency rank in Crafting (double on a critical
+/// success), a critical failure damages the item by 2d6 instead
+fn repair_item(
+    trigger: Trigger<RepairItem>,
+    mut q: Query<(&mut Equipment, Option<&CoreData>)>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut rng: ResMut<DiceRng>,
+    mut cmd: Commands,
+) {
+    let e = trigger.entity();
+    let Ok((mut equipment, core)) = q.get_mut(e) else {
+        return;
+    };
+    let Some(instance) = equipment
+        .slot_mut(trigger.0)
+        .and_then(|slot| slot.0.as_mut())
+    else {
+        return;
+    };
+    let Some(item) = registry
+        .get(&instance.id)
+        .and_then(|handle| items.get(handle.id()))
+    else {
+        return;
+    };
+    if instance.state.wear == 0 {
+        cmd.trigger(ToastEvent(format!(
+            "{} doesn't need repairs",
+            item.get_name()
+        )));
+        return;
+    }
+
+    let modifier = core.map_or(0, |c| c.skill_modifier(Skills::Crafting));
+    let rank = core.map_or(0, |c| {
+        c.training(Skills::Crafting).get_modifier() as u32 / 2
+    });
+    let natural = rng.d20();
+    let degree = DegreeOfSuccess::from_check(natural, natural as i32 + modifier as i32, REPAIR_DC);
+    let state = &mut instance.state;
+    match degree {
+        DegreeOfSuccess::CriticalSuccess => {
+            state.wear = state.wear.saturating_sub(10 + 10 * rank);
+        }
+        DegreeOfSuccess::Success => state.wear = state.wear.saturating_sub(5 + 5 * rank),
+        DegreeOfSuccess::Failure => (),
+        DegreeOfSuccess::CriticalFailure => {
+            let was_broken = state.is_broken(item.durability());
+            state.wear += rng.roll_dice(2, 6);
+            if !was_broken && state.is_broken(item.durability()) {
+                cmd.trigger_targets(ItemBroken(instance.id.clone()), e);
+            }
+        }
+    }
+    cmd.trigger(ToastEvent(format!(
+        "Repairing {}: {degree:?}",
+        item.get_name()
+    )));
+}
+
+fn repair_on_input(q: Query<(Entity, &ActionState<Inputs>), With<PlayerRoot>>, mut cmd: Commands) {
+    let Ok((e, input)) = q.get_single() else {
+        return;
+    };
+    if input.just_pressed(&Inputs::Repair) {
+        cmd.trigger_targets(RepairItem(EquipSlot::MainHand), e);
+    }
+}
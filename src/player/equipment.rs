@@ -0,0 +1,479 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    items::{
+        inventory::{EquippedBulk, Inventory},
+        registry::{ItemId, ItemRegistry, ItemRegistryReady},
+        ArmorItem, Hands, Item, ItemInstance, ItemSlot, ItemState, ItemType, ShieldItem,
+    },
+    rpg_data::{
+        buffs::{ActiveBuffs, BuffTarget},
+        core::{ArmourClass, CoreData, Stats},
+        turns::{TurnPhase, TurnTick},
+    },
+    toast::ToastEvent,
+};
+
+use super::{equipment_models::EquippedModels, inputs::Inputs, PlayerRoot};
+
+/// PF2e limits how many worn magic items a character can invest in at once
+pub const MAX_WORN_ITEMS: usize = 10;
+
+pub struct EquipmentPlugin;
+
+impl Plugin for EquipmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                update_armor,
+                update_equipped_bulk,
+                raise_shield_on_input,
+                lower_shields.run_if(on_event::<TurnTick>),
+            ),
+        );
+        app.add_observer(equip_item);
+        app.add_observer(raise_shield);
+        app.add_observer(unequip_item);
+        app.add_observer(validate_equipment_references);
+    }
+}
+
+#[derive(Debug, Component, Default, Serialize, Deserialize)]
+/// What a character holds and wears. Nothing here is specific to the player, NPCs can be equipped the same way
+#[require(ArmorPenalties, EquippedModels, EquippedBulk)]
+pub struct Equipment {
+    pub main_hand: ItemSlot,
+    pub off_hand: ItemSlot,
+    /// Held in the off hand, so it can't be used together with an off hand item or a two-handed weapon
+    pub shield: ItemSlot,
+    pub armor: ItemSlot,
+    pub worn: Vec<ItemInstance>,
+}
+
+impl Equipment {
+    pub fn items(&self) -> impl Iterator<Item = &ItemId> {
+        [&self.main_hand, &self.off_hand, &self.shield, &self.armor]
+            .into_iter()
+            .filter_map(|slot| slot.id())
+            .chain(self.worn.iter().map(|instance| &instance.id))
+    }
+
+    /// Every equipped item along with the slot it is in
+    pub fn slots(&self) -> impl Iterator<Item = (EquipSlot, &ItemId)> {
+        [
+            (EquipSlot::MainHand, &self.main_hand),
+            (EquipSlot::OffHand, &self.off_hand),
+            (EquipSlot::Shield, &self.shield),
+            (EquipSlot::Armor, &self.armor),
+        ]
+        .into_iter()
+        .filter_map(|(slot, item)| Some((slot, item.id()?)))
+        .chain(
+            self.worn
+                .iter()
+                .map(|instance| (EquipSlot::Worn, &instance.id)),
+        )
+    }
+
+    /// Every slot but `EquipSlot::Worn`, which holds a list of items instead
+    pub fn slot(&self, slot: EquipSlot) -> Option<&ItemSlot> {
+        match slot {
+            EquipSlot::MainHand => Some(&self.main_hand),
+            EquipSlot::OffHand => Some(&self.off_hand),
+            EquipSlot::Shield => Some(&self.shield),
+            EquipSlot::Armor => Some(&self.armor),
+            EquipSlot::Worn => None,
+        }
+    }
+
+    /// Every slot but `EquipSlot::Worn`, which holds a list of items instead
+    pub fn slot_mut(&mut self, slot: EquipSlot) -> Option<&mut ItemSlot> {
+        match slot {
+            EquipSlot::MainHand => Some(&mut self.main_hand),
+            EquipSlot::OffHand => Some(&mut self.off_hand),
+            EquipSlot::Shield => Some(&mut self.shield),
+            EquipSlot::Armor => Some(&mut self.armor),
+            EquipSlot::Worn => None,
+        }
+    }
+
+    /// Empties the first slot holding `id` and returns the state of what it held
+    fn take(&mut self, id: &str) -> Option<ItemState> {
+        for slot in [
+            &mut self.main_hand,
+            &mut self.off_hand,
+            &mut self.shield,
+            &mut self.armor,
+        ] {
+            if slot.id().is_some_and(|slot_id| slot_id == id) {
+                return slot.0.take().map(|instance| instance.state);
+            }
+        }
+        let index = self.worn.iter().position(|w| w.id == id)?;
+        Some(self.worn.remove(index).state)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EquipSlot {
+    MainHand,
+    OffHand,
+    Shield,
+    Armor,
+    Worn,
+}
+
+/// Trigger on an entity with `Equipment` to equip an item.
+///
+/// If the entity has an `Inventory` the item is taken from it, and whatever the slot held before goes back into it.
+/// Requests that break the equipment rules are refused with a toast.
+#[derive(Debug, Event, Clone)]
+pub struct EquipItem {
+    pub item: ItemId,
+    pub slot: EquipSlot,
+}
+
+/// Trigger on an entity with `Equipment` to unequip an item, returning it to the `Inventory` if there is one
+#[derive(Debug, Event, Clone)]
+pub struct UnequipItem(pub ItemId);
+
+/// Penalties from the worn armor after the wearer's Strength is taken into account
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArmorPenalties {
+    /// Applied to Strength and Dexterity based skill checks, as a negative number
+    pub check: i16,
+    /// Speed penalty in feet
+    pub speed: u32,
+}
+
+/// While present the equipped shield adds its bonus to AC. Inserted by `RaiseShield` and removed at the start of the
+/// next turn
+#[derive(Debug, Component)]
+pub struct ShieldRaised;
+
+/// Trigger on an entity with `Equipment` to Raise a Shield
+#[derive(Debug, Event, Clone, Copy)]
+pub struct RaiseShield;
+
+/// AC = 10 + Dexterity (capped by armor) + armor proficiency + armor item bonus + raised shield bonus
+pub fn armor_class(core: &CoreData, armor: Option<&ArmorItem>, shield: Option<&ShieldItem>) -> u32 {
+    let dex = core.modifier(Stats::Dexterity);
+    let dex = armor.map_or(dex, |a| dex.min(a.dex_cap));
+    let armor_bonus = armor.map_or(0, |a| a.ac_bonus as i16);
+    let shield_bonus = shield.map_or(0, |s| s.ac_bonus as i16);
+    (10 + dex + core.proficiency(core.armor_training) + armor_bonus + shield_bonus).max(0) as u32
+}
+
+fn resolve<'a>(
+    slot: &ItemSlot,
+    registry: &ItemRegistry,
+    items: &'a Assets<ItemType>,
+) -> Option<&'a ItemType> {
+    let handle = registry.get(slot.id()?)?;
+    items.get(handle.id())
+}
+
+/// Checks the slot rules, returning why `item` can't go into `slot`
+fn check_equip(
+    equipment: &Equipment,
+    item: &ItemType,
+    slot: EquipSlot,
+    registry: &ItemRegistry,
+    items: &Assets<ItemType>,
+) -> Result<(), &'static str> {
+    let two_handed_held = matches!(
+        resolve(&equipment.main_hand, registry, items),
+        Some(ItemType::Weapon(w)) if w.hands == Hands::Two
+    );
+    match (slot, item) {
+        (EquipSlot::MainHand, ItemType::Weapon(w)) if w.hands == Hands::Two => {
+            if equipment.off_hand.0.is_some() || equipment.shield.0.is_some() {
+                Err("it needs both hands free")
+            } else {
+                Ok(())
+            }
+        }
+        (EquipSlot::MainHand, ItemType::Weapon(_) | ItemType::Basic(_)) => Ok(()),
+        (EquipSlot::OffHand, ItemType::Weapon(w)) if w.hands == Hands::Two => {
+            Err("two-handed weapons are wielded from the main hand")
+        }
+        (EquipSlot::OffHand, ItemType::Weapon(_) | ItemType::Basic(_)) => {
+            if two_handed_held {
+                Err("the main hand weapon needs both hands")
+            } else if equipment.shield.0.is_some() {
+                Err("the off hand is holding a shield")
+            } else {
+                Ok(())
+            }
+        }
+        (EquipSlot::Shield, ItemType::Shield(_)) => {
+            if two_handed_held {
+                Err("the main hand weapon needs both hands")
+            } else if equipment.off_hand.0.is_some() {
+                Err("the off hand is not free")
+            } else {
+                Ok(())
+            }
+        }
+        (EquipSlot::Armor, ItemType::Armor(_)) => Ok(()),
+        (EquipSlot::Worn, ItemType::Basic(_)) => {
+            if equipment.worn.len() >= MAX_WORN_ITEMS {
+                Err("too many items are worn already")
+            } else {
+                Ok(())
+            }
+        }
+        _ => Err("it doesn't fit that slot"),
+    }
+}
+
+fn equip_item(
+    trigger: Trigger<EquipItem>,
+    mut q: Query<(&mut Equipment, Option<&mut Inventory>)>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let e = trigger.entity();
+    let Ok((mut equipment, mut inventory)) = q.get_mut(e) else {
+        return;
+    };
+    let Some(handle) = registry.get(&trigger.item) else {
+        registry.check_reference(&trigger.item, &format!("Equip request for {e}"), &mut cmd);
+        return;
+    };
+    let Some(item) = items.get(handle.id()) else {
+        return;
+    };
+    if let Err(reason) = check_equip(&equipment, item, trigger.slot, &registry, &items) {
+        cmd.trigger(ToastEvent(format!(
+            "Cannot equip {}: {reason}",
+            item.get_name()
+        )));
+        return;
+    }
+    let state = match inventory.as_mut() {
+        Some(inventory) => {
+            let Some(state) = inventory.take_one(handle) else {
+                cmd.trigger(ToastEvent(format!(
+                    "Cannot equip {}: it is not in the inventory",
+                    item.get_name()
+                )));
+                return;
+            };
+            state
+        }
+        None => ItemState::default(),
+    };
+
+    if trigger.slot == EquipSlot::Worn {
+        equipment.worn.push(ItemInstance {
+            id: trigger.item.clone(),
+            state,
+        });
+        return;
+    }
+    let Some(slot) = equipment.slot_mut(trigger.slot) else {
+        return;
+    };
+    let replaced = std::mem::replace(slot, ItemSlot::with_state(trigger.item.clone(), state));
+    if let (Some(mut inventory), Some(previous)) = (inventory, replaced.0) {
+        if let Some(handle) = registry.get(&previous.id) {
+            inventory.add_with_state(handle.clone(), previous.state, 1);
+        }
+    }
+}
+
+fn unequip_item(
+    trigger: Trigger<UnequipItem>,
+    mut q: Query<(&mut Equipment, Option<&mut Inventory>)>,
+    registry: Res<ItemRegistry>,
+) {
+    let Ok((mut equipment, inventory)) = q.get_mut(trigger.entity()) else {
+        return;
+    };
+    let Some(state) = equipment.take(&trigger.0) else {
+        return;
+    };
+    if let (Some(mut inventory), Some(handle)) = (inventory, registry.get(&trigger.0)) {
+        inventory.add_with_state(handle.clone(), state, 1);
+    }
+}
+
+fn update_armor(
+    mut q: Query<(
+        &Equipment,
+        &mut CoreData,
+        &mut ArmorPenalties,
+        Option<&ActiveBuffs>,
+        Has<ShieldRaised>,
+    )>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+) {
+    for (equipment, mut core, mut penalties, buffs, shield_raised) in q.iter_mut() {
+        let armor = match resolve(&equipment.armor, &registry, &items) {
+            Some(ItemType::Armor(armor)) => Some(armor),
+            _ => None,
+        };
+        let shield = match resolve(&equipment.shield, &registry, &items) {
+            Some(ItemType::Shield(shield)) if shield_raised => Some(shield),
+            _ => None,
+        };
+        let buff = buffs.map_or(0, |b| b.total(BuffTarget::ArmorClass));
+        let ac = (armor_class(&core, armor, shield) as i32 + buff as i32).max(0) as u32;
+        // only write on change so `Changed<CoreData>` stays meaningful
+        if core.ac.0 != ac {
+            core.ac = ArmourClass(ac);
+        }
+        let strength = core.modifier(Stats::Strength);
+        let current = armor.map_or_else(ArmorPenalties::default, |armor| ArmorPenalties {
+            check: armor.check_penalty_for(strength),
+            speed: armor.speed_penalty_for(strength),
+        });
+        penalties.set_if_neq(current);
+    }
+}
+
+/// Held and worn items count towards the Bulk a character carries
+fn update_equipped_bulk(
+    mut q: Query<(&Equipment, &mut EquippedBulk)>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+) {
+    for (equipment, mut equipped) in q.iter_mut() {
+        let tenths = equipment
+            .items()
+            .filter_map(|id| items.get(registry.get(id)?.id()))
+            .map(|item| item.get_bulk().tenths())
+            .sum();
+        equipped.set_if_neq(EquippedBulk(tenths));
+    }
+}
+
+fn raise_shield(
+    trigger: Trigger<RaiseShield>,
+    q: Query<&Equipment>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let Ok(equipment) = q.get(trigger.entity()) else {
+        return;
+    };
+    let Some(ItemType::Shield(shield)) = resolve(&equipment.shield, &registry, &items) else {
+        cmd.trigger(ToastEvent("No shield to raise".to_owned()));
+        return;
+    };
+    cmd.entity(trigger.entity()).insert(ShieldRaised);
+    cmd.trigger(ToastEvent(format!("Raised {}", shield.get_name())));
+}
+
+fn lower_shields(
+    mut events: EventReader<TurnTick>,
+    q: Query<Entity, With<ShieldRaised>>,
+    mut cmd: Commands,
+) {
+    if !events.read().any(|tick| tick.phase == TurnPhase::Start) {
+        return;
+    }
+    for e in q.iter() {
+        cmd.entity(e).remove::<ShieldRaised>();
+    }
+}
+
+fn raise_shield_on_input(
+    q: Query<(Entity, &ActionState<Inputs>), With<PlayerRoot>>,
+    mut cmd: Commands,
+) {
+    let Ok((e, input)) = q.get_single() else {
+        return;
+    };
+    if input.just_pressed(&Inputs::RaiseShield) {
+        cmd.trigger_targets(RaiseShield, e);
+    }
+}
+
+fn validate_equipment_references(
+    _: Trigger<ItemRegistryReady>,
+    q: Query<(Entity, &Equipment)>,
+    registry: Res<ItemRegistry>,
+    mut cmd: Commands,
+) {
+    for (e, equipment) in q.iter() {
+        for id in equipment.items() {
+            registry.check_reference(id, &format!("Equipment of {e}"), &mut cmd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::hashbrown::HashMap;
+
+    use crate::{
+        health::Health,
+        rpg_data::core::{ArmourClass, TrainingLevel},
+    };
+
+    use super::*;
+
+    fn test_core() -> CoreData {
+        CoreData {
+            name: "Test Character".to_owned(),
+            level: 1,
+            base_modifiers: HashMap::from_iter([(Stats::Dexterity, 2)]),
+            skill_levels: HashMap::new(),
+            hp: Health::default(),
+            ac: ArmourClass(0),
+            armor_training: TrainingLevel::Trained,
+            weapon_training: TrainingLevel::Trained,
+            conditions: vec![],
+            speed: 25,
+            formulas: vec![],
+        }
+    }
+
+    fn ac(app: &App, e: Entity) -> u32 {
+        app.world().get::<CoreData>(e).unwrap().ac.0
+    }
+
+    #[test]
+    fn raised_shield_adds_its_bonus_to_ac() {
+        let shield: ItemType =
+            serde_json::from_str(include_str!("../../assets/item/test_shield.item.json")).unwrap();
+        let ItemType::Shield(ShieldItem { ac_bonus, .. }) = shield.clone() else {
+            panic!("test_shield is not a shield");
+        };
+        let mut items = Assets::<ItemType>::default();
+        let mut registry = ItemRegistry::default();
+        registry.insert("test_shield", items.add(shield));
+
+        let mut app = App::new();
+        app.insert_resource(items);
+        app.insert_resource(registry);
+        app.add_systems(Update, update_armor);
+        let e = app
+            .world_mut()
+            .spawn((
+                Equipment {
+                    shield: ItemSlot::new("test_shield"),
+                    ..default()
+                },
+                test_core(),
+            ))
+            .id();
+
+        app.update();
+        let lowered = ac(&app, e);
+        app.world_mut().entity_mut(e).insert(ShieldRaised);
+        app.update();
+        assert_eq!(ac(&app, e), lowered + ac_bonus);
+        app.world_mut().entity_mut(e).remove::<ShieldRaised>();
+        app.update();
+        assert_eq!(ac(&app, e), lowered);
+    }
+}
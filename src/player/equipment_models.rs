@@ -0,0 +1,120 @@
+use bevy::{prelude::*, scene::SceneInstanceReady, utils::hashbrown::HashMap};
+
+use crate::items::{
+    registry::{ItemId, ItemRegistry},
+    Item, ItemType,
+};
+
+use super::equipment::{EquipSlot, Equipment};
+
+pub struct EquipmentModelsPlugin;
+
+impl Plugin for EquipmentModelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, sync_equipment_models);
+        app.add_observer(retry_on_scene_ready);
+    }
+}
+
+/// Maps the socket names used by item models to the bone names of this entity's rig. Sockets without an entry are
+/// looked up as bone names directly
+#[derive(Debug, Component, Clone, Default)]
+pub struct Sockets(pub HashMap<String, String>);
+
+impl Sockets {
+    pub fn bone<'a>(&'a self, socket: &'a str) -> &'a str {
+        self.0.get(socket).map_or(socket, |bone| bone.as_str())
+    }
+}
+
+/// The model entities currently attached for each equipped item
+#[derive(Debug, Component, Default)]
+pub struct EquippedModels {
+    models: HashMap<(EquipSlot, ItemId), Entity>,
+    /// Socket bones found so far, by bone name
+    bones: HashMap<String, Entity>,
+    /// Set when a bone couldn't be found, the rig is only searched again once a scene below the entity is ready
+    waiting_for_scene: bool,
+}
+
+/// Spawns the models of newly equipped items under their socket bone and despawns the ones no longer equipped. Items
+/// whose bone isn't spawned yet (the rig scene is still loading) are retried once a scene has finished spawning
+fn sync_equipment_models(
+    mut q: Query<(Entity, &Equipment, Option<&Sockets>, &mut EquippedModels)>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    assets: Res<AssetServer>,
+    mut cmd: Commands,
+) {
+    for (e, equipment, sockets, mut models) in q.iter_mut() {
+        let equipped: Vec<(EquipSlot, ItemId)> = equipment
+            .slots()
+            .map(|(slot, id)| (slot, id.clone()))
+            .collect();
+        models.models.retain(|key, model| {
+            let keep = equipped.contains(key);
+            if !keep {
+                cmd.entity(*model).despawn_recursive();
+            }
+            keep
+        });
+        for key in equipped {
+            if models.models.contains_key(&key) {
+                continue;
+            }
+            let Some(item) = registry.get(&key.1).and_then(|h| items.get(h.id())) else {
+                continue;
+            };
+            let Some(model) = item.get_model() else {
+                continue;
+            };
+            let bone_name = sockets.map_or(model.socket.as_str(), |s| s.bone(&model.socket));
+            let cached = models.bones.get(bone_name).copied();
+            let bone = match cached {
+                Some(bone) if names.contains(bone) => Some(bone),
+                _ if models.waiting_for_scene => None,
+                _ => {
+                    let found = children
+                        .iter_descendants(e)
+                        .find(|child| names.get(*child).is_ok_and(|n| n.as_str() == bone_name));
+                    match found {
+                        Some(bone) => {
+                            models.bones.insert(bone_name.to_owned(), bone);
+                        }
+                        None => models.waiting_for_scene = true,
+                    }
+                    found
+                }
+            };
+            let Some(bone) = bone else {
+                continue;
+            };
+            let entity = cmd
+                .spawn((
+                    Name::new(format!("{} Model", item.get_name())),
+                    SceneRoot(item.assets().handles.model.clone().unwrap_or_else(|| {
+                        assets.load(GltfAssetLabel::Scene(0).from_asset(model.path.clone()))
+                    })),
+                    model.transform(),
+                ))
+                .set_parent(bone)
+                .id();
+            models.models.insert(key, entity);
+        }
+    }
+}
+
+fn retry_on_scene_ready(
+    trigger: Trigger<SceneInstanceReady>,
+    parents: Query<&Parent>,
+    mut q: Query<&mut EquippedModels>,
+) {
+    let scene = trigger.entity();
+    for e in std::iter::once(scene).chain(parents.iter_ancestors(scene)) {
+        if let Ok(mut models) = q.get_mut(e) {
+            models.waiting_for_scene = false;
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+
+use crate::{
+    items::{
+        inventory::Inventory,
+        registry::ItemRegistry,
+        runes::{Rune, WeaponRunes},
+        Item, ItemInstance, ItemSlot, ItemType,
+    },
+    toast::ToastEvent,
+};
+
+use super::equipment::{EquipSlot, Equipment};
+
+pub struct EtchingPlugin;
+
+impl Plugin for EtchingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(etch_rune);
+        app.add_observer(transfer_rune);
+    }
+}
+
+/// Trigger on an entity with `Equipment` to etch a rune onto the weapon in `slot`
+#[derive(Debug, Event, Clone, Copy)]
+pub struct EtchRune {
+    pub slot: EquipSlot,
+    pub rune: Rune,
+}
+
+/// A weapon taking part in a rune transfer
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuneWeapon {
+    Equipped(EquipSlot),
+    /// A copy in the `Inventory`, copies of the same weapon are told apart by their state
+    Inventory(ItemInstance),
+}
+
+/// Trigger on an entity with `Equipment` to move a rune between two of its weapons, equipped or carried in its
+/// `Inventory`
+#[derive(Debug, Event, Clone)]
+pub struct TransferRune {
+    pub from: RuneWeapon,
+    pub to: RuneWeapon,
+    pub rune: Rune,
+}
+
+/// Runes of the weapon in `slot`, along with the weapon's name
+fn weapon_runes<'a>(
+    slot: Option<&'a mut ItemSlot>,
+    registry: &ItemRegistry,
+    items: &'a Assets<ItemType>,
+) -> Option<(&'a String, &'a mut WeaponRunes)> {
+    let instance = slot?.0.as_mut()?;
+    let item = items.get(registry.get(&instance.id)?.id())?;
+    match item {
+        ItemType::Weapon(_) => Some((item.get_name(), &mut instance.state.runes)),
+        _ => None,
+    }
+}
+
+fn etch_rune(
+    trigger: Trigger<EtchRune>,
+    mut q: Query<&mut Equipment>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let Ok(mut equipment) = q.get_mut(trigger.entity()) else {
+        return;
+    };
+    let Some((name, runes)) = weapon_runes(equipment.slot_mut(trigger.slot), &registry, &items)
+    else {
+        cmd.trigger(ToastEvent(
+            "Runes can only be etched onto weapons".to_owned(),
+        ));
+        return;
+    };
+    let message = match runes.etch(trigger.rune) {
+        Ok(()) => format!("Etched {:?} onto {name}", trigger.rune),
+        Err(e) => format!("Can't etch {:?} onto {name}: {e}", trigger.rune),
+    };
+    cmd.trigger(ToastEvent(message));
+}
+
+/// Name and a copy of the weapon at `location`
+fn find_weapon<'a>(
+    location: &RuneWeapon,
+    equipment: &Equipment,
+    inventory: Option<&Inventory>,
+    registry: &ItemRegistry,
+    items: &'a Assets<ItemType>,
+) -> Option<(&'a String, ItemInstance)> {
+    let instance = match location {
+        RuneWeapon::Equipped(slot) => equipment.slot(*slot)?.0.clone()?,
+        RuneWeapon::Inventory(instance) => {
+            let handle = registry.get(&instance.id)?;
+            if inventory?.count_with_state(handle, &instance.state) == 0 {
+                return None;
+            }
+            instance.clone()
+        }
+    };
+    let item = items.get(registry.get(&instance.id)?.id())?;
+    match item {
+        ItemType::Weapon(_) => Some((item.get_name(), instance)),
+        _ => None,
+    }
+}
+
+/// Writes the new runes of `instance` back to where it came from
+fn store_runes(
+    location: &RuneWeapon,
+    instance: &ItemInstance,
+    runes: WeaponRunes,
+    equipment: &mut Equipment,
+    inventory: Option<&mut Inventory>,
+    registry: &ItemRegistry,
+) {
+    match location {
+        RuneWeapon::Equipped(slot) => {
+            if let Some(stored) = equipment.slot_mut(*slot).and_then(|s| s.0.as_mut()) {
+                stored.state.runes = runes;
+            }
+        }
+        RuneWeapon::Inventory(_) => {
+            let (Some(inventory), Some(handle)) = (inventory, registry.get(&instance.id)) else {
+                return;
+            };
+            let mut state = instance.state.clone();
+            state.runes = runes;
+            inventory.set_state(handle, &instance.state, state);
+        }
+    }
+}
+
+fn transfer_rune(
+    trigger: Trigger<TransferRune>,
+    mut q: Query<(&mut Equipment, Option<&mut Inventory>)>,
+    registry: Res<ItemRegistry>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let Ok((mut equipment, mut inventory)) = q.get_mut(trigger.entity()) else {
+        return;
+    };
+    let event = trigger.event();
+    let Some((name, from)) = find_weapon(
+        &event.from,
+        &equipment,
+        inventory.as_deref(),
+        &registry,
+        &items,
+    ) else {
+        cmd.trigger(ToastEvent(
+            "Runes can only be transferred from weapons".to_owned(),
+        ));
+        return;
+    };
+    let Some((_, to)) = find_weapon(
+        &event.to,
+        &equipment,
+        inventory.as_deref(),
+        &registry,
+        &items,
+    ) else {
+        cmd.trigger(ToastEvent(
+            "Runes can only be transferred to weapons".to_owned(),
+        ));
+        return;
+    };
+    // two copies in the same inventory stack are still different weapons
+    let same_weapon = event.from == event.to
+        && match &event.from {
+            RuneWeapon::Equipped(_) => true,
+            RuneWeapon::Inventory(instance) => registry.get(&instance.id).is_none_or(|handle| {
+                inventory
+                    .as_deref()
+                    .is_none_or(|inventory| inventory.count_with_state(handle, &instance.state) < 2)
+            }),
+        };
+    if same_weapon {
+        cmd.trigger(ToastEvent(format!(
+            "Can't transfer {:?} from {name} onto itself",
+            event.rune
+        )));
+        return;
+    }
+    let mut from_runes = from.state.runes.clone();
+    let mut to_runes = to.state.runes.clone();
+    if let Err(e) = from_runes.transfer(&mut to_runes, event.rune) {
+        cmd.trigger(ToastEvent(format!(
+            "Can't transfer {:?} from {name}: {e}",
+            event.rune
+        )));
+        return;
+    }
+    cmd.trigger(ToastEvent(format!(
+        "Transferred {:?} from {name}",
+        event.rune
+    )));
+    store_runes(
+        &event.from,
+        &from,
+        from_runes,
+        &mut equipment,
+        inventory.as_deref_mut(),
+        &registry,
+    );
+    store_runes(
+        &event.to,
+        &to,
+        to_runes,
+        &mut equipment,
+        inventory.as_deref_mut(),
+        &registry,
+    );
+}
@@ -20,6 +20,14 @@ pub enum Inputs {
     Dodge,
     Interact,
     Attack,
+    /// Use the first consumable in the inventory
+    QuickUse,
+    /// Try to repair the main hand weapon
+    Repair,
+    /// Start crafting the first known formula
+    Craft,
+    /// Raise the equipped shield until the start of the next turn
+    RaiseShield,
 }
 
 pub fn player_root_bundle() -> InputManagerBundle<Inputs> {
@@ -34,6 +42,10 @@ pub fn player_root_bundle() -> InputManagerBundle<Inputs> {
             .with(Inputs::Dodge, KeyCode::ShiftLeft)
             .with(Inputs::Interact, KeyCode::KeyE)
             .with(Inputs::Jump, KeyCode::Space)
-            .with(Inputs::Attack, MouseButton::Left),
+            .with(Inputs::Attack, MouseButton::Left)
+            .with(Inputs::QuickUse, KeyCode::KeyQ)
+            .with(Inputs::Repair, KeyCode::KeyR)
+            .with(Inputs::Craft, KeyCode::KeyC)
+            .with(Inputs::RaiseShield, MouseButton::Right),
     )
 }
@@ -0,0 +1,154 @@
+use avian3d::prelude::CollidingEntities;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    items::{
+        inventory::{EquippedBulk, Inventory, InventoryFull},
+        loot::{LootChest, OpenChest},
+        pickup::WorldItem,
+        Item, ItemType,
+    },
+    rpg_data::core::CoreData,
+    settings::GameSettings,
+    toast::ToastEvent,
+};
+
+use super::{inputs::Inputs, PlayerRoot};
+
+pub struct InteractPlugin;
+
+impl Plugin for InteractPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_prompt);
+        app.add_systems(Update, (update_prompt, interact_on_input).chain());
+    }
+}
+
+#[derive(Component)]
+struct InteractPrompt;
+
+type InRange<'w, 's, 'a, T> =
+    Query<'w, 's, (Entity, &'a T, &'a CollidingEntities, &'a GlobalTransform)>;
+
+fn setup_prompt(mut cmd: Commands, settings: Res<GameSettings>, assets: Res<AssetServer>) {
+    cmd.spawn((
+        InteractPrompt,
+        Text::default(),
+        TextFont {
+            font: assets.load(settings.font.regular.clone()),
+            font_size: 24.0,
+            ..default()
+        },
+        Node {
+            align_self: AlignSelf::End,
+            justify_self: JustifySelf::Center,
+            margin: UiRect::bottom(Val::Px(80.0)),
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+/// The closest interactable whose sensor the player is standing in
+fn closest_in_range<'a, T: Component>(
+    player: Entity,
+    position: Vec3,
+    q: &'a InRange<T>,
+) -> Option<(Entity, &'a T)> {
+    q.iter()
+        .filter(|(_, _, colliding, _)| colliding.contains(&player))
+        .min_by(|(.., a), (.., b)| {
+            let a = a.translation().distance_squared(position);
+            let b = b.translation().distance_squared(position);
+            a.total_cmp(&b)
+        })
+        .map(|(e, interactable, ..)| (e, interactable))
+}
+
+fn update_prompt(
+    q_player: Query<(Entity, &GlobalTransform), With<PlayerRoot>>,
+    q_items: InRange<WorldItem>,
+    q_chests: InRange<LootChest>,
+    mut q_prompt: Query<(&mut Text, &mut Visibility), With<InteractPrompt>>,
+    items: Res<Assets<ItemType>>,
+) {
+    let Ok((mut text, mut visibility)) = q_prompt.get_single_mut() else {
+        return;
+    };
+    let prompt = q_player.get_single().ok().and_then(|(player, transform)| {
+        let position = transform.translation();
+        if let Some((_, world_item)) = closest_in_range(player, position, &q_items) {
+            let item = items.get(world_item.item.id())?;
+            return Some(format!("[E] Pick up {}", item.get_name()));
+        }
+        closest_in_range(player, position, &q_chests).map(|_| "[E] Open chest".to_owned())
+    });
+    let Some(prompt) = prompt else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    if text.0 != prompt {
+        text.0 = prompt;
+    }
+    visibility.set_if_neq(Visibility::Inherited);
+}
+
+/// Items in range are picked up before any chest is opened
+fn interact_on_input(
+    mut q_player: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ActionState<Inputs>,
+            &mut Inventory,
+            Option<&CoreData>,
+            Option<&EquippedBulk>,
+        ),
+        With<PlayerRoot>,
+    >,
+    q_items: InRange<WorldItem>,
+    q_chests: InRange<LootChest>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let Ok((player, transform, input, mut inventory, core, equipped)) = q_player.get_single_mut()
+    else {
+        return;
+    };
+    if !input.just_pressed(&Inputs::Interact) {
+        return;
+    }
+    let position = transform.translation();
+    let Some((e, world_item)) = closest_in_range(player, position, &q_items) else {
+        if let Some((chest, _)) = closest_in_range(player, position, &q_chests) {
+            cmd.trigger_targets(OpenChest, chest);
+        }
+        return;
+    };
+    // wait for the item to load so the toast can name it
+    let Some(item) = items.get(world_item.item.id()) else {
+        return;
+    };
+    // too heavy items stay on the ground
+    if !inventory.fits(core, equipped, item, world_item.count, &items) {
+        cmd.trigger_targets(
+            InventoryFull {
+                item: item.get_name().clone(),
+            },
+            player,
+        );
+        return;
+    }
+    inventory.add_with_state(
+        world_item.item.clone(),
+        world_item.state.clone(),
+        world_item.count,
+    );
+    cmd.entity(e).despawn_recursive();
+    let message = match world_item.count {
+        1 => format!("Picked up: {}", item.get_name()),
+        count => format!("Picked up: {} x{count}", item.get_name()),
+    };
+    cmd.trigger(ToastEvent(message));
+}
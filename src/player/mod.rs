@@ -7,19 +7,44 @@ use bevy::{
         experimental::taa::TemporalAntiAliasing,
     },
     prelude::*,
+    utils::hashbrown::HashMap,
 };
 use bevy_tnua::prelude::TnuaController;
 use bevy_tnua_avian3d::TnuaAvian3dSensorShape;
-use inputs::PlayerInputsPlugin;
+use durability::DurabilityPlugin;
+use equipment::{Equipment, EquipmentPlugin};
+use equipment_models::{EquipmentModelsPlugin, Sockets};
+use etching::EtchingPlugin;
+use inputs::{Inputs, PlayerInputsPlugin};
+use interact::InteractPlugin;
+use leafwing_input_manager::prelude::ActionState;
+use respawn::{RespawnPlugin, PLAYER_SPAWN};
 use states::PlayerStatesPlugin;
 
 use crate::{
-    items::ItemSlot,
+    combat::{AttacksThisTurn, Faction, Hurtbox},
+    health::{death::KeepOnDeath, display::ShowHealthBar, dying::UsesDyingRules},
+    items::{
+        consumable::UseConsumable,
+        crafting::CraftItem,
+        currency::{Coins, Wallet},
+        inventory::Inventory,
+        runes::{PropertyRune, WeaponRunes},
+        Item, ItemSlot, ItemState, ItemType,
+    },
     level::{EventEndLoadingLevel, EventStartLoadingLevel},
     player,
+    rpg_data::{core::CoreData, sheet::CharacterSheet},
+    toast::ToastEvent,
 };
 
+pub mod durability;
+pub mod equipment;
+pub mod equipment_models;
+pub mod etching;
 pub mod inputs;
+pub mod interact;
+pub mod respawn;
 pub mod states;
 
 pub const PLAYER_HEIGHT: f32 = 1.75;
@@ -33,10 +58,19 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((PlayerInputsPlugin, PlayerStatesPlugin));
+        app.add_plugins((
+            PlayerInputsPlugin,
+            PlayerStatesPlugin,
+            EquipmentPlugin,
+            DurabilityPlugin,
+            InteractPlugin,
+            EtchingPlugin,
+            EquipmentModelsPlugin,
+            RespawnPlugin,
+        ));
         app.init_resource::<PlayerAnimations>();
         app.add_systems(Startup, setup_player);
-        app.add_systems(Update, start_idle_anim);
+        app.add_systems(Update, (start_idle_anim, quick_use_consumable, quick_craft));
         app.add_observer(
             |_: Trigger<EventStartLoadingLevel>,
              mut cmd: Commands,
@@ -71,11 +105,6 @@ struct CameraAxisNode;
 #[derive(Component)]
 pub struct MainCamera;
 
-#[derive(Debug, Component, Default)]
-pub struct PlayerEquipment {
-    weapon: ItemSlot,
-}
-
 #[derive(Resource, Default)]
 pub struct PlayerAnimations {
     animations: Vec<AnimationNodeIndex>,
@@ -107,15 +136,50 @@ fn setup_player(
     player_anim.animations = indices;
     player_anim.graph = Some(graph_handle);
 
+    let mut inventory = Inventory::default();
+    inventory.add(assets.load("item/test_potion.item.json"), 3);
+    inventory.add(assets.load("item/test_elixir.item.json"), 1);
+    inventory.add(assets.load("item/test_basic.item.json"), 2);
+
     cmd.spawn((
         Name::new("Player"),
         PlayerRoot,
-        Transform::from_xyz(0.0, 2.0, 0.0),
+        Transform::from_translation(PLAYER_SPAWN),
         inputs::player_root_bundle(), // add input management
         states::player_root_bundle(), // add states (components only)
-        PlayerEquipment {
-            weapon: ItemSlot(Some(assets.load("item/test_weapon.json"))),
+        Equipment {
+            main_hand: ItemSlot::with_state(
+                "test_weapon",
+                ItemState {
+                    runes: WeaponRunes {
+                        potency: 1,
+                        striking: 1,
+                        property: vec![PropertyRune::Flaming],
+                    },
+                    ..default()
+                },
+            ),
+            armor: ItemSlot::new("test_armor"),
+            ..default()
         },
+        (
+            CharacterSheet(assets.load("character/valeros.json")),
+            UsesDyingRules,
+            KeepOnDeath,
+            ShowHealthBar {
+                height: PLAYER_HEIGHT,
+            },
+            Faction::Player,
+            Hurtbox,
+            AttacksThisTurn::default(),
+            inventory,
+            Wallet(Coins::gold(15)),
+            // mixamo bone names for the sockets item models use
+            Sockets(HashMap::from_iter([
+                ("hand_r".to_owned(), "mixamorig:RightHand".to_owned()),
+                ("hand_l".to_owned(), "mixamorig:LeftHand".to_owned()),
+            ])),
+        ),
         RigidBody::Dynamic,
         Collider::capsule(PLAYER_RADIUS, PLAYER_COLLIDER_LENGTH),
         TnuaController::default(),
@@ -148,6 +212,45 @@ fn setup_player(
     });
 }
 
+fn quick_use_consumable(
+    q: Query<(Entity, &ActionState<Inputs>, &Inventory), With<PlayerRoot>>,
+    items: Res<Assets<ItemType>>,
+    mut cmd: Commands,
+) {
+    let Ok((e, input, inventory)) = q.get_single() else {
+        return;
+    };
+    if !input.just_pressed(&Inputs::QuickUse) {
+        return;
+    }
+    let Some(consumable) = inventory
+        .stacks
+        .iter()
+        .filter_map(|s| items.get(s.item.id()))
+        .find(|item| matches!(item, ItemType::Consumable(_)))
+    else {
+        return;
+    };
+    cmd.trigger_targets(UseConsumable(consumable.get_id().clone()), e);
+}
+
+fn quick_craft(
+    q: Query<(Entity, &ActionState<Inputs>, &CoreData), With<PlayerRoot>>,
+    mut cmd: Commands,
+) {
+    let Ok((e, input, core)) = q.get_single() else {
+        return;
+    };
+    if !input.just_pressed(&Inputs::Craft) {
+        return;
+    }
+    let Some(formula) = core.formulas.first() else {
+        cmd.trigger(ToastEvent("No known formulas".to_owned()));
+        return;
+    };
+    cmd.trigger_targets(CraftItem(formula.clone()), e);
+}
+
 fn start_idle_anim(
     mut cmd: Commands,
     mut q_anim: Query<(Entity, &mut AnimationPlayer), (With<PlayerModel>, Added<AnimationPlayer>)>,
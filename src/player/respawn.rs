@@ -0,0 +1,99 @@
+use avian3d::prelude::LinearVelocity;
+use bevy::prelude::*;
+
+use crate::{
+    game_states::PauseState,
+    health::{
+        death::{Dead, Died},
+        dying::{Dying, Unconscious, Wounded},
+        Health,
+    },
+    rpg_data::conditions::ConditionChanged,
+    settings::GameSettings,
+};
+
+use super::PlayerRoot;
+
+/// Where the player is put back when respawning
+pub const PLAYER_SPAWN: Vec3 = Vec3::new(0.0, 2.0, 0.0);
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(PauseState::GameOver), setup_game_over_screen);
+        app.add_systems(
+            Update,
+            respawn_on_input.run_if(in_state(PauseState::GameOver)),
+        );
+        app.add_observer(game_over_on_player_died);
+    }
+}
+
+fn game_over_on_player_died(
+    trigger: Trigger<Died>,
+    q: Query<(), With<PlayerRoot>>,
+    mut next: ResMut<NextState<PauseState>>,
+) {
+    if !q.contains(trigger.entity()) {
+        return;
+    }
+    next.set(PauseState::GameOver);
+}
+
+fn setup_game_over_screen(
+    mut cmd: Commands,
+    settings: Res<GameSettings>,
+    assets: Res<AssetServer>,
+) {
+    cmd.spawn((
+        Text::new("You died\nPress Enter to respawn"),
+        TextFont {
+            font: assets.load(settings.font.regular.clone()),
+            font_size: 72.0,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            align_self: AlignSelf::Center,
+            justify_self: JustifySelf::Center,
+            ..default()
+        },
+        StateScoped(PauseState::GameOver),
+    ));
+}
+
+/// Brings the player back at full health at the spawn point and resumes the game
+fn respawn_on_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut q: Query<
+        (
+            Entity,
+            &mut Health,
+            &mut Transform,
+            Option<&mut LinearVelocity>,
+        ),
+        (With<PlayerRoot>, With<Dead>),
+    >,
+    mut next: ResMut<NextState<PauseState>>,
+    mut cmd: Commands,
+) {
+    if !keyboard.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    let Ok((player, mut hp, mut transform, velocity)) = q.get_single_mut() else {
+        return;
+    };
+    hp.current = hp.max;
+    hp.temp = None;
+    transform.translation = PLAYER_SPAWN;
+    if let Some(mut velocity) = velocity {
+        velocity.0 = Vec3::ZERO;
+    }
+    cmd.entity(player)
+        .remove::<(Dead, Dying, Wounded, Unconscious)>();
+    for condition in ["dying", "wounded", "unconscious"] {
+        cmd.trigger_targets(ConditionChanged::lost(condition), player);
+    }
+    next.set(PauseState::Running);
+}
@@ -2,14 +2,30 @@ use core::f32;
 use std::time::{Duration, Instant};
 
 use crate::{
+    combat::{multiple_attack_penalty, AttacksThisTurn, Faction, Hitbox, Strike},
     game_states::MouseState,
-    items::{ItemType, WeaponItem},
-    rpg_data::core::SECONDS_PER_ACTION,
+    health::{dying::Unconscious, invulnerable::GrantInvulnerability, HealthAffect},
+    items::{
+        inventory::{Encumbered, ENCUMBERED_SPEED_PENALTY},
+        registry::ItemRegistry,
+        weapon::{AttackStyle, WeaponTrait},
+        Hands, Item, ItemType, WeaponItem,
+    },
+    rpg_data::{
+        buffs::{ActiveBuffs, BuffTarget},
+        core::{CoreData, Stats, SECONDS_PER_ACTION},
+        dice::DiceRng,
+    },
+    toast::ToastEvent,
 };
 
 use super::{
-    inputs::Inputs, CameraAxisNode, PlayerEquipment, PlayerRoot, PLAYER_COLLIDER_FLOAT_HEIGHT,
+    equipment::{ArmorPenalties, Equipment},
+    inputs::Inputs,
+    CameraAxisNode, PlayerRoot, PLAYER_COLLIDER_FLOAT_HEIGHT, PLAYER_COLLIDER_HEIGHT,
+    PLAYER_RADIUS,
 };
+use avian3d::prelude::Collider;
 use bevy::prelude::*;
 use bevy_tnua::{
     prelude::{TnuaBuiltinWalk, TnuaController},
@@ -23,6 +39,7 @@ const TO_RADIANS: f32 = f32::consts::PI / 180.0; // 2 * pi / 360_deg = pi / 180_
 const PLAYER_SPEED: f32 = 25.0 / SECONDS_PER_ACTION;
 const PLAYER_DODGE_SPEED: f32 = PLAYER_SPEED * 1.5;
 const PLAYER_TURN_SPEED: f32 = 45.0 * TO_RADIANS;
+const PLAYER_ATTACK_REACH: f32 = 1.5;
 
 pub struct PlayerStatesPlugin;
 
@@ -34,6 +51,7 @@ impl Plugin for PlayerStatesPlugin {
                 .run_if(in_state(MouseState::Captured))
                 .in_set(TnuaUserControlsSystemSet),
         );
+        app.add_systems(Update, grant_dodge_iframes);
     }
 }
 
@@ -45,12 +63,15 @@ pub struct StateDodge {
     dir: Vec3,
     started: Instant,
     duration: Duration,
+    /// How long from the start of the dodge the player ignores avoidable damage
+    iframes: Duration,
 }
 
 #[derive(Component, Clone, Default)]
 pub struct StateAttack {
     weapon: Option<WeaponItem>,
     time: Option<Timer>,
+    hitbox: Option<Entity>,
 }
 
 #[derive(Event)]
@@ -82,6 +103,7 @@ fn build_state_dodge(_: &StateMoving, params: ((), Vec2)) -> Option<StateDodge>
         dir: Vec3::new(move_dir.x, 0.0, move_dir.y),
         started: Instant::now(),
         duration: Duration::from_secs_f32(0.5),
+        iframes: Duration::from_secs_f32(0.3),
     })
 }
 
@@ -89,23 +111,48 @@ fn build_state_dodge(_: &StateMoving, params: ((), Vec2)) -> Option<StateDodge>
 
 fn player_state_move(
     mut query: Query<
-        (&mut TnuaController, &mut Transform, &ActionState<Inputs>),
-        (With<PlayerRoot>, With<StateMoving>, Without<CameraAxisNode>),
+        (
+            &mut TnuaController,
+            &mut Transform,
+            &ActionState<Inputs>,
+            Option<&CoreData>,
+            Option<&ArmorPenalties>,
+            Option<&ActiveBuffs>,
+            Has<Encumbered>,
+        ),
+        (
+            With<PlayerRoot>,
+            With<StateMoving>,
+            Without<CameraAxisNode>,
+            Without<Unconscious>,
+        ),
     >,
     mut q_camera: Query<&mut Transform, (With<CameraAxisNode>, Without<PlayerRoot>)>,
     time: Res<Time>,
 ) {
-    let Ok((mut body, mut trans, input)) = query.get_single_mut() else {
+    let Ok((mut body, mut trans, input, core, armor, buffs, encumbered)) = query.get_single_mut()
+    else {
         return;
     };
     let Ok(mut cam_trans) = q_camera.get_single_mut() else {
         return;
     };
+    let speed = match core {
+        Some(core) => {
+            let buff = buffs.map_or(0, |b| b.total(BuffTarget::Speed));
+            let mut feet = (core.speed as i32 + buff as i32).max(0) as u32;
+            feet = feet.saturating_sub(armor.map_or(0, |a| a.speed));
+            if encumbered {
+                feet = feet.saturating_sub(ENCUMBERED_SPEED_PENALTY);
+            }
+            feet as f32 / SECONDS_PER_ACTION
+        }
+        None => PLAYER_SPEED,
+    };
     let movement = input.axis_pair(&Inputs::Move);
     let look = input.axis_pair(&Inputs::Look);
-    let intended_velocity = ((trans.forward() * movement.y) + (trans.right() * movement.x))
-        .normalize_or_zero()
-        * PLAYER_SPEED;
+    let intended_velocity =
+        ((trans.forward() * movement.y) + (trans.right() * movement.x)).normalize_or_zero() * speed;
     body.basis(TnuaBuiltinWalk {
         desired_velocity: intended_velocity,
         float_height: PLAYER_COLLIDER_FLOAT_HEIGHT,
@@ -121,7 +168,10 @@ fn player_state_move(
 
 fn player_state_dodge(
     mut cmd: Commands,
-    mut query: Query<(Entity, &mut TnuaController, &Transform, &StateDodge), With<PlayerRoot>>,
+    mut query: Query<
+        (Entity, &mut TnuaController, &Transform, &StateDodge),
+        (With<PlayerRoot>, Without<Unconscious>),
+    >,
 ) {
     let Ok((e, mut body, trans, dodge)) = query.get_single_mut() else {
         return;
@@ -139,26 +189,102 @@ fn player_state_dodge(
     }
 }
 
+fn grant_dodge_iframes(q: Query<(Entity, &StateDodge), Added<StateDodge>>, mut cmd: Commands) {
+    for (e, dodge) in q.iter() {
+        cmd.trigger_targets(GrantInvulnerability(dodge.iframes), e);
+    }
+}
+
 fn init_state_attack(
     _: Trigger<InitAttackDataEvent>,
     items: Res<Assets<ItemType>>,
-    mut q: Query<(Entity, &mut StateAttack, &PlayerEquipment)>,
+    registry: Res<ItemRegistry>,
+    mut q: Query<(
+        Entity,
+        &mut StateAttack,
+        &mut AttacksThisTurn,
+        &Equipment,
+        Option<&CoreData>,
+        Option<&Faction>,
+    )>,
+    mut rng: ResMut<DiceRng>,
     mut cmd: Commands,
 ) {
-    let Ok((e, mut attack, equipment)) = q.get_single_mut() else {
+    let Ok((e, mut attack, mut attacks, equipment, core, faction)) = q.get_single_mut() else {
         warn!("Failed to get needed entity/components for handling `InitAttackDataEvent`");
         return;
     };
-    let Some(handle) = &equipment.weapon.0 else {
+    let Some(instance) = &equipment.main_hand.0 else {
         cmd.entity(e).insert(Done::Failure);
         return;
     };
-    let Some(ItemType::Weapon(weapon)) = items.get(handle.id()) else {
+    let Some(ItemType::Weapon(weapon)) = registry
+        .get(&instance.id)
+        .and_then(|handle| items.get(handle.id()))
+    else {
         cmd.entity(e).insert(Done::Failure);
         return;
     };
+    if instance.state.is_broken(weapon.durability) {
+        cmd.trigger(ToastEvent(format!("{} is broken", weapon.get_name())));
+        cmd.entity(e).insert(Done::Failure);
+        return;
+    }
+
+    let strength = core.map_or(0, |c| c.modifier(Stats::Strength));
+    let attack_modifier = if weapon.has_trait(WeaponTrait::Finesse) {
+        strength.max(core.map_or(0, |c| c.modifier(Stats::Dexterity)))
+    } else {
+        strength
+    };
+    let proficiency = core.map_or(0, |c| c.proficiency(c.weapon_training));
+    let runes = &instance.state.runes;
+    let bonus = (attack_modifier + proficiency) as i32
+        + weapon.stats(runes).attack_bonus
+        + multiple_attack_penalty(attacks.0, weapon.has_trait(WeaponTrait::Agile));
+    // a one-handed weapon is held in both hands whenever the other hand is free
+    let two_handed = weapon.hands == Hands::Two
+        || (equipment.off_hand.0.is_none() && equipment.shield.0.is_none());
+    let damage = weapon.roll_damage(runes, two_handed, strength, attacks.0, &mut rng);
+    attacks.0 += 1;
+
+    let reach = if weapon.has_trait(WeaponTrait::Reach) {
+        PLAYER_ATTACK_REACH * 2.0
+    } else {
+        PLAYER_ATTACK_REACH
+    };
+    let width = match weapon.attack_style {
+        AttackStyle::Slash => PLAYER_RADIUS * 4.0,
+        AttackStyle::Thrust => PLAYER_RADIUS * 1.5,
+        AttackStyle::Smash => PLAYER_RADIUS * 2.5,
+    };
+    let affect = HealthAffect {
+        damage: damage.normal,
+        ..default()
+    }
+    .with_source(e);
+    let critical = HealthAffect {
+        damage: damage.critical,
+        critical: true,
+        ..default()
+    }
+    .with_source(e);
+    let hitbox = cmd
+        .spawn((
+            Name::new("Attack Hitbox"),
+            Hitbox::new(affect, faction.copied()).with_strike(Strike {
+                bonus,
+                critical,
+                sweep: weapon.has_trait(WeaponTrait::Sweep),
+            }),
+            Collider::cuboid(width, PLAYER_COLLIDER_HEIGHT, reach),
+            Transform::from_xyz(0.0, 0.0, -(PLAYER_RADIUS + reach / 2.0)),
+        ))
+        .set_parent(e)
+        .id();
     attack.weapon = Some(weapon.clone());
     attack.time = Some(Timer::from_seconds(weapon.attack_duration, TimerMode::Once));
+    attack.hitbox = Some(hitbox);
     info!("Starting attack");
 }
 
@@ -175,6 +301,9 @@ fn player_state_attack(
     };
     timer.tick(time.delta());
     if timer.just_finished() {
+        if let Some(hitbox) = state.hitbox.take() {
+            cmd.entity(hitbox).despawn_recursive();
+        }
         cmd.entity(e).insert(Done::Success);
     }
 }
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_states::PauseState;
+
+pub struct BuffsPlugin;
+
+impl Plugin for BuffsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, tick_buffs.run_if(in_state(PauseState::Running)));
+        app.add_observer(grant_buff);
+    }
+}
+
+#[derive(Debug, Reflect, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BuffTarget {
+    ArmorClass,
+    /// In feet
+    Speed,
+}
+
+/// A temporary bonus (or penalty, if negative) as declared in data
+#[derive(Debug, Reflect, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Buff {
+    /// Granting a buff with the same name again refreshes it instead of stacking
+    pub name: String,
+    pub target: BuffTarget,
+    pub amount: i16,
+    /// In seconds
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveBuff {
+    pub buff: Buff,
+    pub timer: Timer,
+}
+
+#[derive(Debug, Component, Clone, Default)]
+pub struct ActiveBuffs(pub Vec<ActiveBuff>);
+
+impl ActiveBuffs {
+    pub fn total(&self, target: BuffTarget) -> i16 {
+        self.0
+            .iter()
+            .filter(|active| active.buff.target == target)
+            .map(|active| active.buff.amount)
+            .sum()
+    }
+}
+
+/// Trigger on an entity to give it a `Buff`
+#[derive(Debug, Event, Clone)]
+pub struct GrantBuff(pub Buff);
+
+fn grant_buff(
+    trigger: Trigger<GrantBuff>,
+    mut q: Query<Option<&mut ActiveBuffs>>,
+    mut cmd: Commands,
+) {
+    let Ok(buffs) = q.get_mut(trigger.entity()) else {
+        return;
+    };
+    let active = ActiveBuff {
+        buff: trigger.0.clone(),
+        timer: Timer::new(
+            Duration::from_secs_f32(trigger.0.duration.max(0.0)),
+            TimerMode::Once,
+        ),
+    };
+    let Some(mut buffs) = buffs else {
+        cmd.entity(trigger.entity())
+            .insert(ActiveBuffs(vec![active]));
+        return;
+    };
+    buffs.0.retain(|b| b.buff.name != active.buff.name);
+    buffs.0.push(active);
+}
+
+fn tick_buffs(mut q: Query<&mut ActiveBuffs>, time: Res<Time>) {
+    for mut buffs in q.iter_mut() {
+        buffs
+            .0
+            .retain_mut(|active| !active.timer.tick(time.delta()).finished());
+    }
+}
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+/// Triggered on an entity whenever one of its conditions is gained, lost, or changes value
+#[derive(Debug, Event, Clone)]
+pub struct ConditionChanged {
+    pub condition: String,
+    /// The new value for valued conditions (dying 2, wounded 1, ...)
+    pub value: Option<u32>,
+    pub active: bool,
+}
+
+impl ConditionChanged {
+    pub fn gained(condition: impl Into<String>, value: Option<u32>) -> Self {
+        Self {
+            condition: condition.into(),
+            value,
+            active: true,
+        }
+    }
+
+    pub fn lost(condition: impl Into<String>) -> Self {
+        Self {
+            condition: condition.into(),
+            value: None,
+            active: false,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use bevy::{prelude::*, utils::hashbrown::HashMap};
 use serde::{Deserialize, Serialize};
 
+use crate::health::Health;
+
 pub const SECONDS_PER_TURN: f32 = 6.0;
 pub const ACTIONS_PER_TURN: u32 = 3;
 pub const SECONDS_PER_ACTION: f32 = SECONDS_PER_TURN / (ACTIONS_PER_TURN as f32);
@@ -8,23 +10,51 @@ pub const SECONDS_PER_ACTION: f32 = SECONDS_PER_TURN / (ACTIONS_PER_TURN as f32)
 #[derive(Component, Reflect, Clone, Serialize, Deserialize)]
 pub struct CoreData {
     pub name: String,
+    #[serde(default)]
+    pub level: i16,
     pub base_modifiers: HashMap<Stats, i16>,
     pub skill_levels: HashMap<Skills, TrainingLevel>,
     pub hp: Health,
     pub ac: ArmourClass,
+    /// Proficiency with the armor (or lack of it) the character wears, the gear itself is added from their equipment
+    #[serde(default)]
+    pub armor_training: TrainingLevel,
+    /// Proficiency with the weapons the character wields
+    #[serde(default)]
+    pub weapon_training: TrainingLevel,
     pub conditions: Vec<String>,
     pub speed: u32,
+    /// Ids of the crafting recipes this character knows
+    #[serde(default)]
+    pub formulas: Vec<String>,
 }
 
-#[derive(Hash, Reflect, Clone, Serialize, Deserialize)]
-pub struct ArmourClass(pub u32);
+impl CoreData {
+    pub fn modifier(&self, stat: Stats) -> i16 {
+        self.base_modifiers.get(&stat).copied().unwrap_or(0)
+    }
 
-#[derive(Hash, Reflect, Clone, Serialize, Deserialize)]
-pub struct Health {
-    pub current: i32,
-    pub max: u32,
+    pub fn training(&self, skill: Skills) -> TrainingLevel {
+        self.skill_levels.get(&skill).copied().unwrap_or_default()
+    }
+
+    /// Proficiency bonus at this character's level, untrained characters don't add their level
+    pub fn proficiency(&self, training: TrainingLevel) -> i16 {
+        match training {
+            TrainingLevel::Untrained => 0,
+            _ => self.level + training.get_modifier(),
+        }
+    }
+
+    /// Ability modifier plus proficiency, what gets added to a d20 for a skill check
+    pub fn skill_modifier(&self, skill: Skills) -> i16 {
+        self.modifier(skill.associated_stat()) + self.proficiency(self.training(skill))
+    }
 }
 
+#[derive(Hash, Reflect, Clone, Serialize, Deserialize)]
+pub struct ArmourClass(pub u32);
+
 #[derive(Hash, Reflect, Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
 pub enum Stats {
     Strength,
@@ -80,8 +110,9 @@ impl Skills {
     }
 }
 
-#[derive(Hash, Reflect, Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[derive(Debug, Hash, Reflect, Clone, PartialEq, Eq, Copy, Default, Serialize, Deserialize)]
 pub enum TrainingLevel {
+    #[default]
     Untrained,
     Trained,
     Expert,
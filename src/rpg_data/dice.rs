@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Shared RNG for every roll in the game. Seed it with `DiceRng::seeded` to get reproducible results
+#[derive(Resource)]
+pub struct DiceRng(pub StdRng);
+
+impl Default for DiceRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
+impl DiceRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+
+    pub fn roll(&mut self, sides: u32) -> u32 {
+        self.0.gen_range(1..=sides.max(1))
+    }
+
+    /// Rolls `count` dice with `sides` sides and sums them
+    pub fn roll_dice(&mut self, count: u32, sides: u32) -> u32 {
+        (0..count).map(|_| self.roll(sides)).sum()
+    }
+
+    pub fn d20(&mut self) -> u32 {
+        self.roll(20)
+    }
+
+    /// A flat check is a d20 with no modifiers against a DC
+    pub fn flat_check(&mut self, dc: i32) -> DegreeOfSuccess {
+        let natural = self.d20();
+        DegreeOfSuccess::from_check(natural, natural as i32, dc)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DegreeOfSuccess {
+    CriticalFailure,
+    Failure,
+    Success,
+    CriticalSuccess,
+}
+
+impl DegreeOfSuccess {
+    /// Applies the PF2e degree rules: beating the DC by 10 (or missing by 10) shifts the degree, and a natural 20 or 1 shifts it one further step
+    pub fn from_check(natural: u32, total: i32, dc: i32) -> Self {
+        let degree = if total >= dc + 10 {
+            DegreeOfSuccess::CriticalSuccess
+        } else if total >= dc {
+            DegreeOfSuccess::Success
+        } else if total <= dc - 10 {
+            DegreeOfSuccess::CriticalFailure
+        } else {
+            DegreeOfSuccess::Failure
+        };
+        match natural {
+            20 => degree.upgrade(),
+            1 => degree.downgrade(),
+            _ => degree,
+        }
+    }
+
+    pub fn upgrade(self) -> Self {
+        match self {
+            DegreeOfSuccess::CriticalFailure => DegreeOfSuccess::Failure,
+            DegreeOfSuccess::Failure => DegreeOfSuccess::Success,
+            _ => DegreeOfSuccess::CriticalSuccess,
+        }
+    }
+
+    pub fn downgrade(self) -> Self {
+        match self {
+            DegreeOfSuccess::CriticalSuccess => DegreeOfSuccess::Success,
+            DegreeOfSuccess::Success => DegreeOfSuccess::Failure,
+            _ => DegreeOfSuccess::CriticalFailure,
+        }
+    }
+}
@@ -2,8 +2,10 @@ use std::fs::File;
 
 use bevy::utils::hashbrown::HashMap;
 
+use crate::health::Health;
+
 use super::{
-    core::{ArmourClass, CoreData, Health, Skills, Stats, TrainingLevel},
+    core::{ArmourClass, CoreData, Skills, Stats, TrainingLevel},
     npc::{NpcCombatData, NpcNoncombatData},
     CharacterData, CharacterType,
 };
@@ -15,15 +17,20 @@ pub fn test_serialize_character_asset() {
     let data = CharacterData {
         core: CoreData {
             name: "Test Character".to_owned(),
+            level: 1,
             base_modifiers: stats,
             skill_levels: skills,
             hp: Health {
                 current: 15,
                 max: 20,
+                ..Default::default()
             },
             ac: ArmourClass(15),
+            armor_training: TrainingLevel::Untrained,
+            weapon_training: TrainingLevel::Trained,
             conditions: vec!["flatfoot".to_owned()],
             speed: 25,
+            formulas: vec![],
         },
         char_type: CharacterType::NpcVersatile(
             NpcCombatData { temp: 5 },
@@ -42,6 +49,7 @@ pub fn test_character_valeros() {
     let data = CharacterData {
         core: CoreData {
             name: "Valeros".to_owned(),
+            level: 1,
             base_modifiers: HashMap::from_iter([
                 (Stats::Strength, 4),
                 (Stats::Dexterity, 2),
@@ -51,13 +59,18 @@ pub fn test_character_valeros() {
             hp: Health {
                 current: 25,
                 max: 25,
+                ..Default::default()
             },
             ac: ArmourClass(18),
+            armor_training: TrainingLevel::Trained,
+            weapon_training: TrainingLevel::Expert,
             conditions: vec![],
             speed: 25,
+            formulas: vec!["test_potion".to_owned()],
             skill_levels: HashMap::from_iter([
                 (Skills::Acrobatics, TrainingLevel::Trained),
                 (Skills::Athletics, TrainingLevel::Trained),
+                (Skills::Crafting, TrainingLevel::Trained),
                 (Skills::Diplomacy, TrainingLevel::Trained),
                 (Skills::Intimidation, TrainingLevel::Trained),
                 (Skills::Lore, TrainingLevel::Trained),
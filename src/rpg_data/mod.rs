@@ -4,14 +4,27 @@ use bevy::{
     asset::{AssetLoader, AsyncReadExt},
     prelude::*,
 };
+use buffs::BuffsPlugin;
+use dice::DiceRng;
 use npc::{NpcCombatData, NpcNoncombatData};
 use player::PlayerData;
 use serde::{Deserialize, Serialize};
+use sheet::CharacterSheetPlugin;
+use turns::TurnsPlugin;
 
+#[cfg(debug_assertions)]
+use crate::asset_errors::toast_load_failures;
+use crate::asset_errors::{DataAssetError, Validate, ValidationError};
+
+pub mod buffs;
+pub mod conditions;
 pub mod core;
+pub mod dice;
 pub mod file_test;
 pub mod npc;
 pub mod player;
+pub mod sheet;
+pub mod turns;
 
 pub struct RpgDataPlugin;
 
@@ -20,6 +33,10 @@ impl Plugin for RpgDataPlugin {
         file_test::test_serialize_character_asset();
         file_test::test_character_valeros();
         app.register_asset_loader(CharacterDataAssetLoader);
+        #[cfg(debug_assertions)]
+        app.add_systems(Update, toast_load_failures::<CharacterData>);
+        app.init_resource::<DiceRng>();
+        app.add_plugins((TurnsPlugin, CharacterSheetPlugin, BuffsPlugin));
     }
 }
 
@@ -37,24 +54,47 @@ pub enum CharacterType {
     NpcVersatile(NpcCombatData, NpcNoncombatData),
 }
 
+impl Validate for CharacterData {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.core.name.trim().is_empty() {
+            return Err(ValidationError::new("name", "name must not be empty"));
+        }
+        if self.core.hp.max == 0 {
+            return Err(ValidationError::new("max", "max hp must be > 0"));
+        }
+        if self.core.hp.current > self.core.hp.max {
+            return Err(ValidationError::new(
+                "current",
+                "current hp must be <= max hp",
+            ));
+        }
+        Ok(())
+    }
+}
+
 pub struct CharacterDataAssetLoader;
 
 impl AssetLoader for CharacterDataAssetLoader {
     type Asset = CharacterData;
     type Settings = ();
-    type Error = serde_json::Error;
+    type Error = DataAssetError;
 
     async fn load(
         &self,
         reader: &mut dyn bevy::asset::io::Reader,
         _: &Self::Settings,
-        _: &mut bevy::asset::LoadContext<'_>,
+        load_context: &mut bevy::asset::LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
+        let path = load_context.path();
         let mut buffer = String::new();
-        let _ = reader.read_to_string(&mut buffer).await;
-        match serde_json::from_str::<CharacterData>(buffer.as_str()) {
-            Ok(value) => Ok(value),
-            Err(err) => Err(err),
-        }
+        reader
+            .read_to_string(&mut buffer)
+            .await
+            .map_err(|e| DataAssetError::io(path, e))?;
+        let data = serde_json::from_str::<CharacterData>(buffer.as_str())
+            .map_err(|e| DataAssetError::json(path, e))?;
+        data.validate()
+            .map_err(|e| DataAssetError::invalid(path, &buffer, e))?;
+        Ok(data)
     }
 }
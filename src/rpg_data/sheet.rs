@@ -0,0 +1,81 @@
+use std::fs::File;
+
+use bevy::prelude::*;
+
+use crate::health::Health;
+
+use super::{core::CoreData, CharacterData};
+
+pub struct CharacterSheetPlugin;
+
+impl Plugin for CharacterSheetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (apply_character_sheets, sync_health_to_sheet));
+        app.add_observer(save_character_sheet);
+    }
+}
+
+/// Links an entity to the `CharacterData` asset it was spawned from.
+///
+/// Once the asset is loaded the entity receives its `CoreData` and `Health`. From then on the `Health` component is
+/// authoritative and any change to it is written back into the entity's `CoreData`. The asset is shared by every entity
+/// spawned from it and is only updated when one of them is saved.
+#[derive(Debug, Component, Clone)]
+pub struct CharacterSheet(pub Handle<CharacterData>);
+
+/// Trigger on an entity with a `CharacterSheet` to write its current character data back to disk
+#[derive(Debug, Event)]
+pub struct SaveCharacterSheet;
+
+fn apply_character_sheets(
+    q: Query<(Entity, &CharacterSheet), Without<CoreData>>,
+    sheets: Res<Assets<CharacterData>>,
+    mut cmd: Commands,
+) {
+    for (e, sheet) in q.iter() {
+        let Some(data) = sheets.get(sheet.0.id()) else {
+            continue;
+        };
+        cmd.entity(e)
+            .insert((data.core.clone(), data.core.hp.clone()));
+    }
+}
+
+fn sync_health_to_sheet(
+    mut q: Query<(&Health, &mut CoreData), (With<CharacterSheet>, Changed<Health>)>,
+) {
+    for (hp, mut core) in q.iter_mut() {
+        core.hp = hp.clone();
+    }
+}
+
+fn save_character_sheet(
+    trigger: Trigger<SaveCharacterSheet>,
+    q: Query<(&CharacterSheet, &CoreData, Option<&Health>)>,
+    mut sheets: ResMut<Assets<CharacterData>>,
+    assets: Res<AssetServer>,
+) {
+    let Ok((sheet, core, hp)) = q.get(trigger.entity()) else {
+        return;
+    };
+    let (Some(data), Some(path)) = (sheets.get_mut(sheet.0.id()), assets.get_path(sheet.0.id()))
+    else {
+        warn!(
+            "Cannot save character sheet for {}, it is not loaded",
+            trigger.entity()
+        );
+        return;
+    };
+    data.core = core.clone();
+    // the health sync may not have run yet this frame
+    if let Some(hp) = hp {
+        data.core.hp = hp.clone();
+    }
+    let Ok(file) = File::create(format!("assets/{}", path.path().display())) else {
+        warn!("Failed to open '{path}' for saving");
+        return;
+    };
+    if let Err(e) = serde_json::to_writer_pretty(file, &*data) {
+        warn!("Failed to serialize character sheet '{path}': {e}");
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use crate::game_states::PauseState;
+
+use super::core::SECONDS_PER_TURN;
+
+pub struct TurnsPlugin;
+
+impl Plugin for TurnsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TurnClock>();
+        app.add_event::<TurnTick>();
+        app.add_systems(
+            Update,
+            tick_turn_clock.run_if(in_state(PauseState::Running)),
+        );
+    }
+}
+
+/// Splits real-time play into `SECONDS_PER_TURN` windows so that turn based rules (recovery checks, persistent damage, etc.) have something to hook into
+#[derive(Resource)]
+pub struct TurnClock {
+    pub turn: u64,
+    timer: Timer,
+}
+
+impl Default for TurnClock {
+    fn default() -> Self {
+        Self {
+            turn: 0,
+            timer: Timer::from_seconds(SECONDS_PER_TURN, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnPhase {
+    Start,
+    End,
+}
+
+/// Sent whenever a turn window ends and the next one begins. The end of turn `n` is always sent before the start of turn `n + 1`
+#[derive(Debug, Event, Clone, Copy)]
+pub struct TurnTick {
+    pub turn: u64,
+    pub phase: TurnPhase,
+}
+
+fn tick_turn_clock(
+    mut clock: ResMut<TurnClock>,
+    time: Res<Time>,
+    mut writer: EventWriter<TurnTick>,
+) {
+    clock.timer.tick(time.delta());
+    for _ in 0..clock.timer.times_finished_this_tick() {
+        writer.send(TurnTick {
+            turn: clock.turn,
+            phase: TurnPhase::End,
+        });
+        clock.turn += 1;
+        writer.send(TurnTick {
+            turn: clock.turn,
+            phase: TurnPhase::Start,
+        });
+    }
+}